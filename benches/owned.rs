@@ -0,0 +1,106 @@
+use std::hint::black_box;
+
+use criterion::{
+    criterion_group, criterion_main, BenchmarkId, Criterion, Throughput,
+};
+use rkyv::{
+    de::Pool, rancor, util::AlignedVec, Archive, Deserialize, Serialize,
+};
+use rkyv_util::{cache::CachedArchive, owned::OwnedArchive};
+
+#[derive(Archive, Deserialize, Serialize)]
+pub struct Record {
+    id: u64,
+    name: String,
+    scores: Vec<u32>,
+}
+
+fn records(len: usize) -> Vec<Record> {
+    (0..len as u64)
+        .map(|id| Record {
+            id,
+            name: format!("record {id}"),
+            scores: (0..8).collect(),
+        })
+        .collect()
+}
+
+/// Small and large archives of the same shape.
+fn archives() -> [(&'static str, AlignedVec); 2] {
+    [
+        (
+            "small",
+            rkyv::to_bytes::<rancor::Error>(&records(1)).unwrap(),
+        ),
+        (
+            "large",
+            rkyv::to_bytes::<rancor::Error>(&records(10_000)).unwrap(),
+        ),
+    ]
+}
+
+fn validation(c: &mut Criterion) {
+    let mut group = c.benchmark_group("validation");
+    for (name, bytes) in archives() {
+        group.throughput(Throughput::Bytes(bytes.len() as u64));
+        group.bench_with_input(
+            BenchmarkId::from_parameter(name),
+            &bytes,
+            |b, bytes| {
+                b.iter_batched(
+                    || bytes.clone(),
+                    |bytes| {
+                        OwnedArchive::<Vec<Record>, _>::new::<rancor::Error>(
+                            bytes,
+                        )
+                        .unwrap()
+                    },
+                    criterion::BatchSize::SmallInput,
+                )
+            },
+        );
+    }
+    group.finish();
+}
+
+fn deref(c: &mut Criterion) {
+    let mut group = c.benchmark_group("deref");
+    for (name, bytes) in archives() {
+        let archive =
+            OwnedArchive::<Vec<Record>, _>::new::<rancor::Error>(bytes)
+                .unwrap();
+        group.bench_function(name, |b| b.iter(|| black_box(&archive).len()));
+
+        let cached = CachedArchive::new(archive);
+        group.bench_function(format!("{name} cached"), |b| {
+            b.iter(|| black_box(&cached).len())
+        });
+    }
+    group.finish();
+}
+
+fn deserialize(c: &mut Criterion) {
+    let mut group = c.benchmark_group("deserialize");
+    for (name, bytes) in archives() {
+        group.throughput(Throughput::Bytes(bytes.len() as u64));
+        let archive =
+            OwnedArchive::<Vec<Record>, _>::new::<rancor::Error>(bytes)
+                .unwrap();
+        group.bench_function(name, |b| {
+            b.iter(|| archive.deserialize::<rancor::Error>().unwrap())
+        });
+
+        let mut pool = Pool::new();
+        group.bench_function(format!("{name} pooled"), |b| {
+            b.iter(|| {
+                archive
+                    .deserialize_with_pool::<rancor::Error>(&mut pool)
+                    .unwrap()
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, validation, deref, deserialize);
+criterion_main!(benches);
@@ -0,0 +1,162 @@
+//! Archived values viewed through a trait object.
+//!
+//! An [`OwnedArchive`] is typed by the value it holds, so archives of
+//! different types can't share a collection. [`BoxedArchived`] keeps the
+//! archive's bytes alive but exposes the archived value as some `dyn Trait`
+//! that all of them implement.
+
+use core::{fmt, ops::Deref};
+
+use rkyv::{Archive, Portable};
+
+use crate::owned::{access_root, OwnedArchive, StableBytes};
+
+/// Resolves the archived value inside validated bytes as a `Dyn`.
+type Resolve<Dyn> = dyn for<'a> Fn(&'a [u8]) -> &'a Dyn + Send + Sync;
+
+/// An owned archive whose archived value is accessed as a `Dyn`.
+///
+/// # Example
+/// ```
+/// use rkyv::{rancor::Error, util::AlignedVec};
+/// use rkyv_util::{boxed::BoxedArchived, owned::OwnedArchive};
+///
+/// #[derive(rkyv::Archive, rkyv::Serialize)]
+/// pub struct Test {
+///     hello: u8,
+/// }
+///
+/// trait Describe {
+///     fn describe(&self) -> String;
+/// }
+///
+/// impl Describe for ArchivedTest {
+///     fn describe(&self) -> String {
+///         format!("hello is {}", self.hello)
+///     }
+/// }
+///
+/// let bytes = rkyv::to_bytes::<Error>(&Test { hello: 2 }).unwrap();
+/// let owned_archive = OwnedArchive::<Test, _>::new::<Error>(bytes).unwrap();
+///
+/// let boxed = BoxedArchived::<dyn Describe, AlignedVec>::new(
+///     owned_archive,
+///     |archived| archived,
+/// );
+/// assert_eq!(boxed.describe(), "hello is 2");
+/// ```
+pub struct BoxedArchived<Dyn: ?Sized, C> {
+    /// The container holding the validated bytes.
+    container: C,
+    /// Finds the archived value in the container's bytes.
+    resolve: Box<Resolve<Dyn>>,
+}
+
+impl<Dyn: ?Sized, C: StableBytes> BoxedArchived<Dyn, C> {
+    /// Creates a new `BoxedArchived` from an owned archive.
+    ///
+    /// `coerce` turns a reference to the archived value into a `Dyn`. For a
+    /// trait that the archived type implements, `|archived| archived` is
+    /// enough as long as `Dyn` is named, for example with
+    /// `BoxedArchived::<dyn Trait, _>::new`.
+    pub fn new<T, F>(archive: OwnedArchive<T, C>, coerce: F) -> Self
+    where
+        T: Archive + 'static,
+        T::Archived: Portable,
+        F: for<'a> Fn(&'a T::Archived) -> &'a Dyn + Send + Sync + 'static,
+    {
+        let resolve: Box<Resolve<Dyn>> = Box::new(move |bytes| {
+            // # Safety
+            // These are the bytes of the container taken from an
+            // `OwnedArchive<T, C>`, which were validated when it was created
+            // and, by the `StableBytes` contract, have not changed since.
+            coerce(unsafe { access_root::<T::Archived>(bytes) })
+        });
+
+        Self {
+            container: archive.into_container(),
+            resolve,
+        }
+    }
+
+    /// Unwraps the underlying container.
+    pub fn into_container(self) -> C {
+        self.container
+    }
+}
+
+impl<Dyn: ?Sized, C: StableBytes> Deref for BoxedArchived<Dyn, C> {
+    type Target = Dyn;
+
+    fn deref(&self) -> &Self::Target {
+        (self.resolve)(self.container.bytes())
+    }
+}
+
+impl<Dyn: fmt::Debug + ?Sized, C: StableBytes> fmt::Debug
+    for BoxedArchived<Dyn, C>
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.deref().fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rkyv::{rancor, util::AlignedVec, Archive, Serialize};
+
+    use super::BoxedArchived;
+    use crate::owned::OwnedArchive;
+
+    trait Describe {
+        fn describe(&self) -> String;
+    }
+
+    #[derive(Archive, Serialize)]
+    pub struct Point {
+        x: i32,
+        y: i32,
+    }
+
+    impl Describe for ArchivedPoint {
+        fn describe(&self) -> String {
+            format!("point at ({}, {})", self.x, self.y)
+        }
+    }
+
+    #[derive(Archive, Serialize)]
+    pub struct Label {
+        text: String,
+    }
+
+    impl Describe for ArchivedLabel {
+        fn describe(&self) -> String {
+            format!("label {:?}", self.text.as_str())
+        }
+    }
+
+    #[test]
+    fn test_boxed_archived_heterogeneous() {
+        let point =
+            rkyv::to_bytes::<rancor::Error>(&Point { x: 1, y: -2 }).unwrap();
+        let label = rkyv::to_bytes::<rancor::Error>(&Label {
+            text: "hello".to_string(),
+        })
+        .unwrap();
+
+        let values: Vec<BoxedArchived<dyn Describe, AlignedVec>> = vec![
+            BoxedArchived::<dyn Describe, _>::new(
+                OwnedArchive::<Point, _>::new::<rancor::Error>(point).unwrap(),
+                |archived| archived,
+            ),
+            BoxedArchived::<dyn Describe, _>::new(
+                OwnedArchive::<Label, _>::new::<rancor::Error>(label).unwrap(),
+                |archived| archived,
+            ),
+        ];
+
+        let descriptions =
+            values.iter().map(|v| v.describe()).collect::<Vec<_>>();
+        assert_eq!(descriptions, ["point at (1, -2)", "label \"hello\""]);
+    }
+}
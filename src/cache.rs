@@ -0,0 +1,348 @@
+//! Owned archives that keep a deserialized copy next to the archived view.
+//!
+//! Some callers want zero-copy access most of the time but occasionally need
+//! the fully deserialized value. [`OwnedArchiveWithCache`] deserializes at
+//! most once and hands out the same value on every later request.
+//!
+//! [`CachedArchive`] caches something much smaller, the pointer to the
+//! archived root, for read-heavy archives that are dereferenced very often.
+
+use core::{
+    cell::OnceCell,
+    fmt::Debug,
+    ops::Deref,
+    ptr,
+    sync::atomic::{AtomicPtr, Ordering},
+};
+
+use rkyv::{
+    api::high::{HighDeserializer, HighSerializer},
+    ser::allocator::ArenaHandle,
+    util::AlignedVec,
+    Archive, Deserialize, Serialize,
+};
+
+use crate::{
+    compat::ValidateArchive,
+    owned::{ByteBacking, OwnedArchive, StableBytes},
+};
+
+/// An owned archive with a lazily deserialized cache.
+///
+/// Dereferencing gives the archived value, just like [`OwnedArchive`].
+/// [`cached`](Self::cached) deserializes the archive the first time it is
+/// called and returns the stored value afterwards.
+///
+/// # Example
+/// ```
+/// use rkyv::rancor::Error;
+/// use rkyv_util::cache::OwnedArchiveWithCache;
+///
+/// #[derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)]
+/// pub struct Test {
+///     hello: u8,
+/// }
+///
+/// let bytes = rkyv::to_bytes::<Error>(&Test { hello: 2 }).unwrap();
+///
+/// let archive =
+///     OwnedArchiveWithCache::<Test, _>::new::<Error>(bytes).unwrap();
+/// assert_eq!(archive.hello, 2);
+/// assert_eq!(archive.cached::<Error>().unwrap().hello, 2);
+/// ```
+pub struct OwnedArchiveWithCache<T, C> {
+    /// The archive backing the cache.
+    archive: OwnedArchive<T, C>,
+    /// The deserialized value, once it has been requested.
+    cache: OnceCell<T>,
+}
+
+impl<T, C> OwnedArchiveWithCache<T, C> {
+    /// Creates a new `OwnedArchiveWithCache` from a container
+    /// that supports the `StableBytes` interface.
+    ///
+    /// The cache starts out empty.
+    pub fn new<E>(container: C) -> Result<Self, E>
+    where
+        T: Archive,
+        T::Archived: ValidateArchive<E>,
+        E: rkyv::rancor::Source,
+        C: StableBytes,
+    {
+        Ok(Self::from(OwnedArchive::new::<E>(container)?))
+    }
+
+    /// Serializes `value` and keeps it as the cached value.
+    ///
+    /// The value is serialized into an `AlignedVec`, converted into the
+    /// container and validated as with [`new`](Self::new). Keeping the
+    /// original lets the archived and original values be compared without
+    /// deserializing, which is handy in tests and debugging tools.
+    ///
+    /// Both copies are kept in memory for as long as this lives, roughly
+    /// doubling its footprint compared to an archive with an empty cache.
+    ///
+    /// # Example
+    /// ```
+    /// use rkyv::{rancor::Error, util::AlignedVec};
+    /// use rkyv_util::cache::OwnedArchiveWithCache;
+    ///
+    /// #[derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)]
+    /// pub struct Test {
+    ///     hello: u8,
+    /// }
+    ///
+    /// let archive: OwnedArchiveWithCache<Test, AlignedVec> =
+    ///     OwnedArchiveWithCache::new_from_owned_value::<Error>(Test {
+    ///         hello: 2,
+    ///     })
+    ///     .unwrap();
+    /// assert_eq!(archive.hello, 2);
+    /// assert_eq!(archive.cached::<Error>().unwrap().hello, 2);
+    /// ```
+    pub fn new_from_owned_value<E>(value: T) -> Result<Self, E>
+    where
+        T: Archive
+            + for<'a> Serialize<HighSerializer<AlignedVec, ArenaHandle<'a>, E>>,
+        T::Archived: ValidateArchive<E>,
+        E: rkyv::rancor::Source,
+        C: From<AlignedVec> + StableBytes,
+    {
+        let bytes = rkyv::to_bytes::<E>(&value)?;
+        let archive = OwnedArchive::new::<E>(C::from(bytes))?;
+        Ok(Self {
+            archive,
+            cache: OnceCell::from(value),
+        })
+    }
+
+    /// Gets the deserialized value, deserializing it on the first call.
+    ///
+    /// If deserialization fails the cache stays empty, and the next call will
+    /// try again.
+    pub fn cached<E>(&self) -> Result<&T, E>
+    where
+        T: Archive,
+        T::Archived: Deserialize<T, HighDeserializer<E>>,
+        E: rkyv::rancor::Source,
+        C: StableBytes,
+    {
+        if let Some(value) = self.cache.get() {
+            return Ok(value);
+        }
+
+        let value = self.archive.deserialize::<E>()?;
+        Ok(self.cache.get_or_init(|| value))
+    }
+
+    /// Gets the underlying owned archive.
+    pub fn archive(&self) -> &OwnedArchive<T, C> {
+        &self.archive
+    }
+
+    /// Splits into the owned archive and the cached value, if any.
+    pub fn into_parts(self) -> (OwnedArchive<T, C>, Option<T>) {
+        (self.archive, self.cache.into_inner())
+    }
+}
+
+impl<T, C> From<OwnedArchive<T, C>> for OwnedArchiveWithCache<T, C> {
+    fn from(archive: OwnedArchive<T, C>) -> Self {
+        Self {
+            archive,
+            cache: OnceCell::new(),
+        }
+    }
+}
+
+impl<C: StableBytes, T: Archive> Deref for OwnedArchiveWithCache<T, C> {
+    type Target = T::Archived;
+
+    fn deref(&self) -> &Self::Target {
+        self.archive.deref()
+    }
+}
+
+impl<T: Archive, C: StableBytes> Debug for OwnedArchiveWithCache<T, C>
+where
+    T::Archived: Debug,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        self.deref().fmt(f)
+    }
+}
+
+/// An owned archive that resolves its root pointer once.
+///
+/// The first dereference finds the archived root and stores a pointer to it,
+/// and every later one is a single atomic load. The pointer is shared between
+/// threads, so a `CachedArchive` over an `Arc<[u8]>` is `Sync` like the
+/// archive itself.
+///
+/// Only the containers built into this crate are supported, since their bytes
+/// stay in place when the container is moved. Finding the root of those is
+/// already only a few instructions, so the `deref` benchmark is worth running
+/// before reaching for this.
+///
+/// # Example
+/// ```
+/// use std::sync::Arc;
+///
+/// use rkyv::rancor::Error;
+/// use rkyv_util::{cache::CachedArchive, owned::OwnedArchive};
+///
+/// #[derive(rkyv::Archive, rkyv::Serialize)]
+/// pub struct Test {
+///     hello: u8,
+/// }
+///
+/// let bytes = rkyv::to_bytes::<Error>(&Test { hello: 2 }).unwrap();
+/// let bytes: Arc<[u8]> = Arc::from(bytes.as_slice());
+///
+/// let archive = CachedArchive::new(
+///     OwnedArchive::<Test, _>::new::<Error>(bytes).unwrap(),
+/// );
+/// assert_eq!(archive.hello, 2);
+/// ```
+pub struct CachedArchive<T, C> {
+    /// The archive whose root is cached.
+    archive: OwnedArchive<T, C>,
+    /// The archived root, or null until it is first resolved.
+    root: AtomicPtr<u8>,
+}
+
+impl<T, C> CachedArchive<T, C> {
+    /// Creates a new `CachedArchive` wrapping `archive`.
+    ///
+    /// The root is resolved on the first dereference.
+    pub fn new(archive: OwnedArchive<T, C>) -> Self {
+        Self {
+            archive,
+            root: AtomicPtr::new(ptr::null_mut()),
+        }
+    }
+
+    /// Gets the underlying owned archive.
+    pub fn archive(&self) -> &OwnedArchive<T, C> {
+        &self.archive
+    }
+
+    /// Unwraps the underlying owned archive.
+    pub fn into_inner(self) -> OwnedArchive<T, C> {
+        self.archive
+    }
+}
+
+impl<T, C> From<OwnedArchive<T, C>> for CachedArchive<T, C> {
+    fn from(archive: OwnedArchive<T, C>) -> Self {
+        Self::new(archive)
+    }
+}
+
+impl<T: Archive, C: ByteBacking> Deref for CachedArchive<T, C> {
+    type Target = T::Archived;
+
+    fn deref(&self) -> &Self::Target {
+        let mut root = self.root.load(Ordering::Acquire);
+        if root.is_null() {
+            // Racing threads all resolve the same pointer, so it doesn't
+            // matter whose store wins.
+            root = ptr::from_ref(self.archive.deref()).cast_mut().cast();
+            self.root.store(root, Ordering::Release);
+        }
+
+        // # Safety
+        // `root` was resolved from `self.archive`, which is never mutated
+        // through a `CachedArchive`. The containers implementing
+        // `ByteBacking` keep their bytes in place when moved, so the pointer
+        // stays valid for as long as the archive lives.
+        unsafe { &*root.cast::<T::Archived>() }
+    }
+}
+
+impl<T: Archive, C: ByteBacking> Debug for CachedArchive<T, C>
+where
+    T::Archived: Debug,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        self.deref().fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use rkyv::{rancor, Archive, Deserialize, Serialize};
+
+    use super::{CachedArchive, OwnedArchiveWithCache};
+    use crate::owned::OwnedArchive;
+
+    #[derive(Archive, Clone, PartialEq, Deserialize, Serialize, Debug)]
+    #[rkyv(compare(PartialEq), derive(Debug))]
+    pub struct ArchiveStub {
+        hello: u8,
+        world: u64,
+    }
+
+    #[test]
+    fn test_cached_deserializes_once() {
+        let stub = ArchiveStub { hello: 4, world: 5 };
+
+        let bytes = rkyv::to_bytes::<rancor::Error>(&stub).unwrap();
+        let archive: OwnedArchiveWithCache<ArchiveStub, _> =
+            OwnedArchiveWithCache::new::<rancor::Error>(bytes).unwrap();
+        assert_eq!(stub, *archive);
+
+        let first = archive.cached::<rancor::Error>().unwrap();
+        let second = archive.cached::<rancor::Error>().unwrap();
+        assert_eq!(*first, stub);
+
+        // Both calls should hand out the one stored value.
+        assert!(core::ptr::eq(first, second));
+
+        let (_, cached) = archive.into_parts();
+        assert_eq!(cached, Some(stub));
+    }
+
+    #[test]
+    fn test_new_from_owned_value() {
+        let stub = ArchiveStub { hello: 4, world: 5 };
+
+        let archive: OwnedArchiveWithCache<ArchiveStub, Vec<u8>> =
+            OwnedArchiveWithCache::new_from_owned_value::<rancor::Error>(
+                stub.clone(),
+            )
+            .unwrap();
+
+        // The cache is filled from the original value, not deserialized.
+        let (archive, cached) = archive.into_parts();
+        let cached = cached.unwrap();
+        assert_eq!(cached, stub);
+        assert_eq!(archive.hello, cached.hello);
+        assert_eq!(archive.world, cached.world);
+    }
+
+    #[test]
+    fn test_cached_archive_resolves_root_once() {
+        fn assert_sync<T: Sync>(_: &T) {}
+
+        let stub = ArchiveStub { hello: 4, world: 5 };
+        let bytes = rkyv::to_bytes::<rancor::Error>(&stub).unwrap();
+        let bytes: Arc<[u8]> = Arc::from(bytes.as_slice());
+
+        let archive = CachedArchive::new(
+            OwnedArchive::<ArchiveStub, _>::new::<rancor::Error>(bytes)
+                .unwrap(),
+        );
+        assert_sync(&archive);
+
+        let first: *const ArchivedArchiveStub = &*archive;
+        assert_eq!(stub, *archive);
+
+        // Moving the archive must not invalidate the cached root.
+        let archive = Box::new(archive);
+        assert!(core::ptr::eq(first, &**archive));
+        assert!(core::ptr::eq(first, &**archive.archive()));
+    }
+}
@@ -0,0 +1,66 @@
+//! The glue between this crate and the rkyv API it builds on.
+//!
+//! Bounds on rkyv's validation machinery are spelled out in one place here,
+//! so that changes to them between rkyv versions only have to be made once.
+//! This crate currently supports rkyv 0.8.
+
+use rkyv::{api::high::HighValidator, bytecheck::CheckBytes, Portable};
+
+/// An archived type that can be validated with rkyv's high-level validator,
+/// reporting errors as `E`.
+///
+/// This is implemented for every type that meets the bounds, and is
+/// shorthand for them in generic code:
+///
+/// ```
+/// use rkyv::{rancor::Source, Archive};
+/// use rkyv_util::{compat::ValidateArchive, owned::OwnedArchive};
+///
+/// fn load<T, E>(bytes: Vec<u8>) -> Result<OwnedArchive<T, Vec<u8>>, E>
+/// where
+///     T: Archive,
+///     T::Archived: ValidateArchive<E>,
+///     E: Source,
+/// {
+///     OwnedArchive::new::<E>(bytes)
+/// }
+/// ```
+///
+/// Archived types without `CheckBytes` are rejected with an error that says
+/// so, instead of one about rkyv's validator internals:
+///
+/// ```compile_fail,E0277
+/// use rkyv::{rancor::Error, Archive, Place, Portable};
+/// use rkyv_util::owned::OwnedArchive;
+///
+/// struct Unchecked;
+///
+/// // SAFETY: `Unchecked` has no fields and so no layout to get wrong.
+/// unsafe impl Portable for Unchecked {}
+///
+/// struct Test;
+///
+/// impl Archive for Test {
+///     type Archived = Unchecked;
+///     type Resolver = ();
+///
+///     fn resolve(&self, _: (), _: Place<Unchecked>) {}
+/// }
+///
+/// let _ = OwnedArchive::<Test, Vec<u8>>::new::<Error>(Vec::new());
+/// ```
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` cannot be validated by rkyv",
+    note = "validation needs `CheckBytes`, which `#[derive(Archive)]` only \
+            provides with rkyv's `bytecheck` feature enabled",
+    note = "hand-written archived types need `#[derive(CheckBytes)]`"
+)]
+pub trait ValidateArchive<E>:
+    Portable + for<'a> CheckBytes<HighValidator<'a, E>>
+{
+}
+
+impl<T, E> ValidateArchive<E> for T where
+    T: Portable + for<'a> CheckBytes<HighValidator<'a, E>> + ?Sized
+{
+}
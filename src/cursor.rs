@@ -0,0 +1,133 @@
+//! A resumable cursor over the elements of an archived vector.
+
+use rkyv::{vec::ArchivedVec, Archive};
+
+use crate::owned::{OwnedArchive, StableBytes};
+
+/// A positioned reader over the elements of an archived vector.
+///
+/// Unlike a plain iterator, the cursor's position can be saved with
+/// [`position`](Self::position) and restored with [`seek`](Self::seek), which
+/// is handy for reading a large archive a page at a time.
+///
+/// # Example
+/// ```
+/// use rkyv::rancor::Error;
+/// use rkyv_util::owned::OwnedArchive;
+///
+/// let bytes = rkyv::to_bytes::<Error>(&vec![1u32, 2, 3, 4]).unwrap();
+/// let owned_archive =
+///     OwnedArchive::<Vec<u32>, _>::new::<Error>(bytes).unwrap();
+///
+/// let mut cursor = owned_archive.cursor();
+/// cursor.seek(2);
+/// assert_eq!(cursor.next().map(|x| x.to_native()), Some(3));
+/// assert_eq!(cursor.position(), 3);
+/// ```
+pub struct ArchiveCursor<'a, T: Archive> {
+    /// The archived elements being read.
+    elements: &'a [T::Archived],
+    /// The index of the next element to read.
+    position: usize,
+}
+
+impl<'a, T: Archive> ArchiveCursor<'a, T> {
+    /// Creates a new cursor at the start of `elements`.
+    pub fn new(elements: &'a ArchivedVec<T::Archived>) -> Self {
+        Self {
+            elements: elements.as_slice(),
+            position: 0,
+        }
+    }
+
+    /// Gets the index of the next element the cursor will read.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Moves the cursor so the next element read is the one at `position`.
+    ///
+    /// Seeking past the end is allowed; the cursor then yields no more
+    /// elements until it is moved back.
+    pub fn seek(&mut self, position: usize) {
+        self.position = position;
+    }
+
+    /// Gets the elements that have not been read yet.
+    pub fn remaining(&self) -> &'a [T::Archived] {
+        self.elements.get(self.position..).unwrap_or_default()
+    }
+}
+
+impl<'a, T: Archive> Iterator for ArchiveCursor<'a, T> {
+    type Item = &'a T::Archived;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let element = self.elements.get(self.position)?;
+        self.position += 1;
+        Some(element)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.remaining().len();
+        (len, Some(len))
+    }
+}
+
+impl<T: Archive> ExactSizeIterator for ArchiveCursor<'_, T> {}
+
+impl<T: Archive> Clone for ArchiveCursor<'_, T> {
+    fn clone(&self) -> Self {
+        Self {
+            elements: self.elements,
+            position: self.position,
+        }
+    }
+}
+
+impl<T: Archive, C: StableBytes> OwnedArchive<Vec<T>, C> {
+    /// Creates a cursor at the start of the archived elements.
+    pub fn cursor(&self) -> ArchiveCursor<'_, T> {
+        ArchiveCursor::new(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rkyv::{rancor, Archive, Serialize};
+
+    use crate::owned::OwnedArchive;
+
+    #[derive(Archive, Serialize)]
+    pub struct Record {
+        id: u32,
+    }
+
+    #[test]
+    fn test_cursor_seek_and_read() {
+        let records = (0..100).map(|id| Record { id }).collect::<Vec<_>>();
+
+        let bytes = rkyv::to_bytes::<rancor::Error>(&records).unwrap();
+        let owned: OwnedArchive<Vec<Record>, _> =
+            OwnedArchive::new::<rancor::Error>(bytes).unwrap();
+
+        let mut cursor = owned.cursor();
+        assert_eq!(cursor.len(), 100);
+
+        cursor.seek(50);
+        let page = cursor.by_ref().take(10).map(|r| r.id).collect::<Vec<_>>();
+        assert_eq!(page, (50..60).collect::<Vec<_>>());
+        assert_eq!(cursor.position(), 60);
+
+        // A saved position can be restored later.
+        let saved = cursor.position();
+        cursor.seek(0);
+        assert_eq!(cursor.next().unwrap().id, 0);
+        cursor.seek(saved);
+        assert_eq!(cursor.next().unwrap().id, 60);
+        assert_eq!(cursor.remaining().len(), 39);
+
+        cursor.seek(1000);
+        assert!(cursor.next().is_none());
+    }
+}
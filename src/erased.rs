@@ -0,0 +1,136 @@
+//! Owned archives with their archived type erased.
+//!
+//! This makes it possible to keep archives of different types in one
+//! collection, then recover the typed [`OwnedArchive`] when it is needed.
+
+use core::any::{type_name, TypeId};
+
+use crate::owned::OwnedArchive;
+
+/// An owned archive whose type has been erased.
+///
+/// The bytes were validated when the original [`OwnedArchive`] was created,
+/// so [`downcast`](Self::downcast) back to the same type does not need to
+/// validate them again.
+///
+/// # Example
+/// ```
+/// use rkyv::rancor::Error;
+/// use rkyv_util::{erased::ErasedArchive, owned::OwnedArchive};
+///
+/// #[derive(rkyv::Archive, rkyv::Serialize)]
+/// pub struct Test {
+///     hello: u8,
+/// }
+///
+/// let bytes = rkyv::to_bytes::<Error>(&Test { hello: 2 }).unwrap();
+///
+/// let erased: ErasedArchive<_> =
+///     OwnedArchive::<Test, _>::new::<Error>(bytes).unwrap().erase();
+/// assert!(erased.is::<Test>());
+///
+/// let owned_archive = erased.downcast::<Test>().unwrap();
+/// assert_eq!(owned_archive.hello, 2);
+/// ```
+pub struct ErasedArchive<C> {
+    /// The container holding the validated bytes.
+    container: C,
+    /// The type the bytes were validated as.
+    type_id: TypeId,
+    /// The name of the type the bytes were validated as, for debugging.
+    type_name: &'static str,
+}
+
+impl<T: 'static, C> OwnedArchive<T, C> {
+    /// Erases the archived type, keeping the validated bytes.
+    pub fn erase(self) -> ErasedArchive<C> {
+        ErasedArchive {
+            container: self.into_container(),
+            type_id: TypeId::of::<T>(),
+            type_name: type_name::<T>(),
+        }
+    }
+}
+
+impl<C> ErasedArchive<C> {
+    /// Returns whether the bytes were validated as an archive of `T`.
+    pub fn is<T: 'static>(&self) -> bool {
+        self.type_id == TypeId::of::<T>()
+    }
+
+    /// Gets the name of the type the bytes were validated as.
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    /// Recovers the typed `OwnedArchive`.
+    ///
+    /// Returns `self` unchanged if the bytes were validated as a different
+    /// type.
+    pub fn downcast<T: 'static>(self) -> Result<OwnedArchive<T, C>, Self> {
+        if self.is::<T>() {
+            // # Safety
+            // The container was taken from an `OwnedArchive<T, C>` for this
+            // very `T`, so its bytes have already been validated.
+            Ok(unsafe { OwnedArchive::new_unchecked(self.container) })
+        } else {
+            Err(self)
+        }
+    }
+}
+
+impl<C> core::fmt::Debug for ErasedArchive<C> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("ErasedArchive")
+            .field("type_name", &self.type_name)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rkyv::{rancor, util::AlignedVec, Archive, Serialize};
+
+    use super::ErasedArchive;
+    use crate::owned::OwnedArchive;
+
+    #[derive(Archive, Serialize)]
+    pub struct First {
+        hello: u8,
+    }
+
+    #[derive(Archive, Serialize)]
+    pub struct Second {
+        world: u64,
+    }
+
+    #[test]
+    fn test_erased_archive_downcast() {
+        let first =
+            rkyv::to_bytes::<rancor::Error>(&First { hello: 4 }).unwrap();
+        let second =
+            rkyv::to_bytes::<rancor::Error>(&Second { world: 5 }).unwrap();
+
+        let archives: Vec<ErasedArchive<AlignedVec>> = vec![
+            OwnedArchive::<First, _>::new::<rancor::Error>(first)
+                .unwrap()
+                .erase(),
+            OwnedArchive::<Second, _>::new::<rancor::Error>(second)
+                .unwrap()
+                .erase(),
+        ];
+
+        let mut archives = archives.into_iter();
+
+        // The wrong type hands the archive back untouched.
+        let first = match archives.next().unwrap().downcast::<Second>() {
+            Ok(_) => panic!("downcast to the wrong type succeeded"),
+            Err(first) => first,
+        };
+        assert_eq!(first.downcast::<First>().unwrap().hello, 4);
+
+        let second = archives.next().unwrap();
+        assert!(second.is::<Second>());
+        assert_eq!(second.downcast::<Second>().unwrap().world, 5);
+    }
+}
@@ -0,0 +1,256 @@
+//! Containers for archives that follow a magic-number header.
+//!
+//! Files often start with a few bytes identifying their format and version
+//! before the archive itself. [`HeaderedArchive`] checks that header and then
+//! exposes only the bytes after it, so it can be used as the container of an
+//! [`OwnedArchive`](crate::owned::OwnedArchive).
+
+use core::fmt;
+use std::error::Error;
+
+use crate::owned::{sealed, ByteBacking, StableBytes, StableBytesMut};
+
+/// The expected contents of a header.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Header {
+    /// The magic number identifying the format.
+    pub magic: [u8; 4],
+    /// The version of the format.
+    pub version: u8,
+}
+
+impl Header {
+    /// The length of a header in bytes.
+    pub const LEN: usize = 5;
+}
+
+/// An error from checking a header.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HeaderError {
+    /// The buffer is too short to hold a header.
+    TooShort {
+        /// The length of the buffer.
+        len: usize,
+    },
+    /// The magic number did not match.
+    BadMagic {
+        /// The magic number that was found.
+        found: [u8; 4],
+    },
+    /// The magic number matched, but the version did not.
+    UnexpectedVersion {
+        /// The version that was found.
+        found: u8,
+        /// The version that was expected.
+        expected: u8,
+    },
+}
+
+impl fmt::Display for HeaderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TooShort { len } => write!(
+                f,
+                "buffer of {len} bytes is too short for a {}-byte header",
+                Header::LEN,
+            ),
+            Self::BadMagic { found } => {
+                write!(f, "unexpected magic number {found:02x?}")
+            }
+            Self::UnexpectedVersion { found, expected } => write!(
+                f,
+                "unexpected format version {found}, expected {expected}",
+            ),
+        }
+    }
+}
+
+impl Error for HeaderError {}
+
+/// A container whose bytes start with a [`Header`].
+///
+/// Only the bytes after the header are exposed through `StableBytes`.
+///
+/// # Alignment
+/// The archive starts [`Header::LEN`] bytes into the container, so it is
+/// only aligned for archived types with an alignment of 1. Other archives
+/// will fail validation unless the container itself is offset to make up
+/// for the header.
+///
+/// # Example
+/// ```
+/// use rkyv::rancor::Error;
+/// use rkyv_util::{
+///     header::{Header, HeaderedArchive},
+///     owned::OwnedArchive,
+/// };
+///
+/// #[derive(rkyv::Archive, rkyv::Serialize)]
+/// pub struct Test {
+///     hello: u8,
+/// }
+///
+/// const HEADER: Header = Header {
+///     magic: *b"TEST",
+///     version: 1,
+/// };
+///
+/// let mut file = b"TEST\x01".to_vec();
+/// file.extend_from_slice(&rkyv::to_bytes::<Error>(&Test { hello: 2 })?);
+///
+/// let container = HeaderedArchive::new(file, HEADER).unwrap();
+/// let owned_archive = OwnedArchive::<Test, _>::new::<Error>(container)?;
+/// assert_eq!(owned_archive.hello, 2);
+/// # Ok::<(), Error>(())
+/// ```
+#[derive(Clone, Debug)]
+pub struct HeaderedArchive<C> {
+    /// The container holding the header and the archive.
+    container: C,
+}
+
+impl<C: StableBytes> HeaderedArchive<C> {
+    /// Checks the header of `container` against `expected`.
+    pub fn new(container: C, expected: Header) -> Result<Self, HeaderError> {
+        let bytes = container.bytes();
+        if bytes.len() < Header::LEN {
+            return Err(HeaderError::TooShort { len: bytes.len() });
+        }
+
+        let found = [bytes[0], bytes[1], bytes[2], bytes[3]];
+        if found != expected.magic {
+            return Err(HeaderError::BadMagic { found });
+        }
+        if bytes[4] != expected.version {
+            return Err(HeaderError::UnexpectedVersion {
+                found: bytes[4],
+                expected: expected.version,
+            });
+        }
+
+        Ok(Self { container })
+    }
+
+    /// Gets the header bytes.
+    pub fn header_bytes(&self) -> &[u8] {
+        &self.container.bytes()[..Header::LEN]
+    }
+
+    /// Unwraps the underlying container, including its header.
+    pub fn into_inner(self) -> C {
+        self.container
+    }
+}
+
+// The header was checked to fit when the container was created, and the
+// underlying container upholds the contract for the bytes after it.
+
+unsafe impl<C: StableBytes> StableBytes for HeaderedArchive<C> {
+    fn bytes(&self) -> &[u8] {
+        &self.container.bytes()[Header::LEN..]
+    }
+}
+
+unsafe impl<C: StableBytesMut> StableBytesMut for HeaderedArchive<C> {
+    fn bytes_mut(&mut self) -> &mut [u8] {
+        &mut self.container.bytes_mut()[Header::LEN..]
+    }
+}
+
+impl<C: ByteBacking> sealed::Sealed for HeaderedArchive<C> {}
+
+impl<C: ByteBacking> ByteBacking for HeaderedArchive<C> {
+    const MUTABLE: bool = C::MUTABLE;
+
+    fn try_bytes_mut(&mut self) -> Option<&mut [u8]> {
+        self.container
+            .try_bytes_mut()
+            .map(|bytes| &mut bytes[Header::LEN..])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rkyv::{munge::munge, rancor, Archive, Serialize};
+
+    use super::{Header, HeaderError, HeaderedArchive};
+    use crate::owned::OwnedArchive;
+
+    const HEADER: Header = Header {
+        magic: *b"RKYV",
+        version: 3,
+    };
+
+    #[derive(Archive, Serialize)]
+    pub struct Test {
+        kind: u8,
+        payload: [u8; 3],
+    }
+
+    fn file(header: &[u8]) -> Vec<u8> {
+        let mut file = header.to_vec();
+        file.extend_from_slice(
+            &rkyv::to_bytes::<rancor::Error>(&Test {
+                kind: 1,
+                payload: [2, 3, 4],
+            })
+            .unwrap(),
+        );
+        file
+    }
+
+    #[test]
+    fn test_headered_archive_good_header() {
+        let container =
+            HeaderedArchive::new(file(b"RKYV\x03"), HEADER).unwrap();
+        assert_eq!(container.header_bytes(), b"RKYV\x03");
+
+        let owned =
+            OwnedArchive::<Test, _>::new::<rancor::Error>(container).unwrap();
+        assert_eq!(owned.kind, 1);
+        assert_eq!(owned.payload, [2, 3, 4]);
+    }
+
+    #[test]
+    fn test_headered_archive_try_get_mut() {
+        let container =
+            HeaderedArchive::new(file(b"RKYV\x03"), HEADER).unwrap();
+        let mut owned =
+            OwnedArchive::<Test, _>::new::<rancor::Error>(container).unwrap();
+        let sealed = owned.try_get_mut().unwrap();
+        munge!(let ArchivedTest { mut kind, .. } = sealed);
+        *kind = 9;
+        assert_eq!(owned.kind, 9);
+        assert_eq!(owned.container().header_bytes(), b"RKYV\x03");
+
+        let file = file(b"RKYV\x03");
+        let container = HeaderedArchive::new(file.as_slice(), HEADER).unwrap();
+        let mut owned =
+            OwnedArchive::<Test, _>::new::<rancor::Error>(container).unwrap();
+        assert!(owned.try_get_mut().is_none());
+    }
+
+    #[test]
+    fn test_headered_archive_bad_header() {
+        let Err(error) = HeaderedArchive::new(file(b"JSON\x03"), HEADER) else {
+            panic!("a bad magic number was accepted");
+        };
+        assert_eq!(error, HeaderError::BadMagic { found: *b"JSON" });
+
+        let Err(error) = HeaderedArchive::new(file(b"RKYV\x04"), HEADER) else {
+            panic!("an unexpected version was accepted");
+        };
+        assert_eq!(
+            error,
+            HeaderError::UnexpectedVersion {
+                found: 4,
+                expected: 3
+            }
+        );
+
+        let Err(error) = HeaderedArchive::new(b"RKY".to_vec(), HEADER) else {
+            panic!("a truncated header was accepted");
+        };
+        assert_eq!(error, HeaderError::TooShort { len: 3 });
+    }
+}
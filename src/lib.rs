@@ -23,4 +23,20 @@
     13.512-13.512-2.702 2.703-2.702-8.107-8.107z"/%3E%3C/svg%3E
 "#)]
 
+pub mod boxed;
+pub mod cache;
+pub mod compat;
+pub mod cursor;
+pub mod erased;
+pub mod header;
+#[cfg(feature = "mmap")]
+pub mod mmap;
 pub mod owned;
+pub mod prelude;
+pub mod shallow;
+pub mod strict;
+pub mod sync;
+pub mod uninit;
+pub mod weak;
+#[cfg(feature = "zip")]
+pub mod zip;
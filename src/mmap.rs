@@ -0,0 +1,989 @@
+//! Owned archives backed by memory-mapped files.
+//!
+//! A single [`Mmap`] can hold many archives. [`SharedMmap`] lets each of them
+//! be viewed as its own [`OwnedArchive`] while the file is only mapped once.
+
+use core::{
+    fmt,
+    ops::{Deref, Range},
+};
+use std::{
+    error::Error,
+    fs::{self, File, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use memmap2::{Mmap, MmapMut};
+use rkyv::{
+    api::high::HighSerializer,
+    rancor::Source,
+    ser::{allocator::ArenaHandle, Positional, Writer},
+    util::AlignedVec,
+    Archive, Serialize,
+};
+
+use crate::{
+    compat::ValidateArchive,
+    owned::{sealed, ByteBacking, OwnedArchive, StableBytes, StableBytesMut},
+};
+
+/// A range of bytes within a shared memory map.
+///
+/// Cloning a `SharedMmap` only clones the `Arc`, so any number of archives
+/// can view the same mapping.
+///
+/// # Stability
+/// The contents of a memory-mapped file can be changed by other processes
+/// while it is mapped, which is why creating an [`Mmap`] is `unsafe`. The
+/// caller that maps the file takes on the responsibility of making sure it is
+/// not modified for as long as the mapping is alive; that is what allows the
+/// bytes to be treated as stable here.
+#[derive(Clone, Debug)]
+pub struct SharedMmap {
+    /// The mapping holding the bytes.
+    map: Arc<Mmap>,
+    /// The range of the mapping to expose.
+    range: Range<usize>,
+}
+
+impl SharedMmap {
+    /// Creates a new `SharedMmap` over `range` of `map`.
+    ///
+    /// Returns `None` if `range` does not fit within the mapping.
+    pub fn new(map: Arc<Mmap>, range: Range<usize>) -> Option<Self> {
+        map.get(range.clone())?;
+        Some(Self { map, range })
+    }
+
+    /// Gets the underlying memory map.
+    pub fn map(&self) -> &Arc<Mmap> {
+        &self.map
+    }
+
+    /// Gets the range of the mapping this exposes.
+    pub fn range(&self) -> Range<usize> {
+        self.range.clone()
+    }
+}
+
+unsafe impl StableBytes for SharedMmap {
+    fn bytes(&self) -> &[u8] {
+        &self.map[self.range.clone()]
+    }
+}
+
+impl sealed::Sealed for SharedMmap {}
+
+impl ByteBacking for SharedMmap {
+    const MUTABLE: bool = false;
+
+    fn try_bytes_mut(&mut self) -> Option<&mut [u8]> {
+        None
+    }
+}
+
+#[derive(Debug)]
+struct RangeOutOfBounds {
+    range: Range<usize>,
+    len: usize,
+}
+
+impl fmt::Display for RangeOutOfBounds {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "range {}..{} is out of bounds for a mapping of {} bytes",
+            self.range.start, self.range.end, self.len,
+        )
+    }
+}
+
+impl Error for RangeOutOfBounds {}
+
+impl<T> OwnedArchive<T, SharedMmap> {
+    /// Creates a new `OwnedArchive` from `range` of a shared memory map.
+    ///
+    /// This is also how to open one entry of a file holding many archives,
+    /// such as a batch with a directory of `(offset, len)` pairs: pass
+    /// `offset..offset + len`. Only that range is validated, and a range
+    /// that does not fit within the mapping is rejected with an error.
+    ///
+    /// # Example
+    /// ```
+    /// use std::{fs::File, io::Write, sync::Arc};
+    ///
+    /// use memmap2::Mmap;
+    /// use rkyv::rancor::Error;
+    /// use rkyv_util::{mmap::SharedMmap, owned::OwnedArchive};
+    ///
+    /// #[derive(rkyv::Archive, rkyv::Serialize)]
+    /// pub struct Test {
+    ///     hello: u8,
+    /// }
+    ///
+    /// let bytes = rkyv::to_bytes::<Error>(&Test { hello: 2 }).unwrap();
+    /// let path = std::env::temp_dir().join("rkyv_util_shared_mmap_doc");
+    /// File::create(&path).unwrap().write_all(&bytes).unwrap();
+    ///
+    /// // SAFETY: Nothing else modifies the file while it is mapped.
+    /// let file = File::open(&path).unwrap();
+    /// let map = Arc::new(unsafe { Mmap::map(&file) }.unwrap());
+    /// let owned_archive =
+    ///     OwnedArchive::<Test, SharedMmap>::from_shared_mmap::<Error>(
+    ///         map,
+    ///         0..bytes.len(),
+    ///     )
+    ///     .unwrap();
+    /// assert_eq!(owned_archive.hello, 2);
+    /// # std::fs::remove_file(&path).unwrap();
+    /// ```
+    pub fn from_shared_mmap<E>(
+        map: Arc<Mmap>,
+        range: Range<usize>,
+    ) -> Result<Self, E>
+    where
+        T: Archive,
+        T::Archived: ValidateArchive<E>,
+        E: Source,
+    {
+        let len = map.len();
+        let container = SharedMmap::new(map, range.clone())
+            .ok_or_else(|| E::new(RangeOutOfBounds { range, len }))?;
+        Self::new::<E>(container)
+    }
+}
+
+/// An owned archive backed by a shared memory map.
+pub type MmapArchive<T> = OwnedArchive<T, SharedMmap>;
+
+// A mutable mapping is only ever changed through `StableBytesMut`, under the
+// same contract as `SharedMmap`: whoever maps the file makes sure nothing
+// else modifies it while it is mapped.
+unsafe impl StableBytes for MmapMut {
+    fn bytes(&self) -> &[u8] {
+        self
+    }
+}
+
+unsafe impl StableBytesMut for MmapMut {
+    fn bytes_mut(&mut self) -> &mut [u8] {
+        self
+    }
+}
+
+impl sealed::Sealed for MmapMut {}
+
+impl ByteBacking for MmapMut {
+    const MUTABLE: bool = true;
+
+    fn try_bytes_mut(&mut self) -> Option<&mut [u8]> {
+        Some(self)
+    }
+}
+
+impl<T, C: StableBytes> OwnedArchive<T, C> {
+    /// Writes the archive to the file at `path` and maps it mutably.
+    ///
+    /// This is for persisting an archive built in memory and carrying on
+    /// editing it on disk. The file is created, or truncated if it exists.
+    /// The mapping is validated again, although being page-aligned it is
+    /// always aligned at least as well as the original buffer.
+    ///
+    /// # Safety
+    /// The file must not be modified or truncated by anything else for as
+    /// long as the returned archive is alive. See [`SharedMmap`] for
+    /// details.
+    ///
+    /// # Example
+    /// ```
+    /// use rkyv::{munge::munge, rancor::Error};
+    /// use rkyv_util::owned::OwnedArchive;
+    ///
+    /// #[derive(rkyv::Archive, rkyv::Serialize)]
+    /// pub struct Test {
+    ///     hello: u8,
+    /// }
+    ///
+    /// let bytes = rkyv::to_bytes::<Error>(&Test { hello: 2 }).unwrap();
+    /// let owned_archive =
+    ///     OwnedArchive::<Test, _>::new::<Error>(bytes).unwrap();
+    ///
+    /// let path = std::env::temp_dir().join("rkyv_util_to_mmap_file_doc");
+    /// // SAFETY: Nothing else modifies the file while it is mapped.
+    /// let mut mapped =
+    ///     unsafe { owned_archive.to_mmap_file::<Error>(&path) }.unwrap();
+    /// munge!(let ArchivedTest { mut hello } = mapped.get_mut());
+    /// *hello = 3;
+    /// assert_eq!(mapped.hello, 3);
+    /// # drop(mapped);
+    /// # std::fs::remove_file(&path).unwrap();
+    /// ```
+    pub unsafe fn to_mmap_file<E>(
+        &self,
+        path: impl AsRef<Path>,
+    ) -> Result<OwnedArchive<T, MmapMut>, E>
+    where
+        T: Archive,
+        T::Archived: ValidateArchive<E>,
+        E: Source,
+    {
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(path)
+            .map_err(E::new)?;
+        file.write_all(self.as_bytes()).map_err(E::new)?;
+        // # Safety
+        // The caller guarantees that the file is not modified while mapped.
+        let map = unsafe { MmapMut::map_mut(&file) }.map_err(E::new)?;
+        OwnedArchive::new::<E>(map)
+    }
+}
+
+/// A writer that serializes straight into a memory-mapped file.
+///
+/// This builds an archive in a file without serializing it into a buffer
+/// and copying it over. The file is grown and remapped whenever the archive
+/// outgrows it, and truncated to the archive's length once serialization
+/// finishes.
+///
+/// # Example
+/// ```
+/// use std::fs::OpenOptions;
+///
+/// use rkyv::rancor::Error;
+/// use rkyv_util::mmap::MmapWriter;
+///
+/// #[derive(rkyv::Archive, rkyv::Serialize)]
+/// pub struct Test {
+///     hello: u8,
+/// }
+///
+/// let path = std::env::temp_dir().join("rkyv_util_mmap_writer_doc");
+/// let file = OpenOptions::new()
+///     .read(true)
+///     .write(true)
+///     .create(true)
+///     .truncate(true)
+///     .open(&path)
+///     .unwrap();
+///
+/// // SAFETY: Nothing else modifies the file while it is mapped.
+/// let writer = unsafe { MmapWriter::new(file, 4096) }.unwrap();
+/// let owned_archive =
+///     writer.serialize::<Test, Error>(&Test { hello: 2 }).unwrap();
+/// assert_eq!(owned_archive.hello, 2);
+/// # std::fs::remove_file(&path).unwrap();
+/// ```
+pub struct MmapWriter {
+    /// The file being written, which must be open for reading and writing.
+    file: File,
+    /// A mapping of the whole file.
+    map: MmapMut,
+    /// The number of bytes written so far.
+    pos: usize,
+}
+
+impl MmapWriter {
+    /// Creates a new `MmapWriter` that starts with `capacity` bytes of
+    /// `file` mapped.
+    ///
+    /// The file must be open for reading and writing. Any existing contents
+    /// are overwritten.
+    ///
+    /// # Safety
+    /// The file must not be modified or truncated by anything else for as
+    /// long as the writer, or the archive it produces, is alive. See
+    /// [`SharedMmap`] for details.
+    pub unsafe fn new(file: File, capacity: usize) -> io::Result<Self> {
+        file.set_len(capacity.max(1) as u64)?;
+        // # Safety
+        // The caller guarantees that the file is not modified while mapped.
+        let map = unsafe { MmapMut::map_mut(&file) }?;
+        Ok(Self { file, map, pos: 0 })
+    }
+
+    /// Serializes `value` into the file and validates the result.
+    ///
+    /// The file is left holding exactly the archive, so it can be loaded
+    /// again later with [`load_best_effort`] or [`load_dir`].
+    pub fn serialize<T, E>(
+        self,
+        value: &T,
+    ) -> Result<OwnedArchive<T, MmapMut>, E>
+    where
+        T: Archive
+            + for<'a> Serialize<HighSerializer<Self, ArenaHandle<'a>, E>>,
+        T::Archived: ValidateArchive<E>,
+        E: Source,
+    {
+        let mut writer = rkyv::api::high::to_bytes_in::<_, E>(value, self)?;
+        writer.resize(writer.pos).map_err(E::new)?;
+        OwnedArchive::new::<E>(writer.map)
+    }
+
+    /// Resizes the file to `len` bytes and maps all of it.
+    fn resize(&mut self, len: usize) -> io::Result<()> {
+        self.file.set_len(len as u64)?;
+        // # Safety
+        // The caller of `new` guaranteed that the file is not modified while
+        // mapped.
+        self.map = unsafe { MmapMut::map_mut(&self.file) }?;
+        Ok(())
+    }
+}
+
+impl Positional for MmapWriter {
+    fn pos(&self) -> usize {
+        self.pos
+    }
+}
+
+impl<E: Source> Writer<E> for MmapWriter {
+    fn write(&mut self, bytes: &[u8]) -> Result<(), E> {
+        let end = self.pos + bytes.len();
+        if end > self.map.len() {
+            let len = end.max(self.map.len() * 2);
+            self.resize(len).map_err(E::new)?;
+        }
+        self.map[self.pos..end].copy_from_slice(bytes);
+        self.pos = end;
+        Ok(())
+    }
+}
+
+/// What [`load_dir`] does with a file that fails validation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OnInvalid {
+    /// Leave the file out of the results.
+    Skip,
+    /// Stop loading and return the validation error.
+    Fail,
+}
+
+/// Maps and validates every regular file in `dir` as an archive of `T`.
+///
+/// The results are sorted by path. Subdirectories are not descended into.
+/// Errors reading the directory or mapping a file are always returned;
+/// `on_invalid` decides what happens to files that map but fail validation.
+///
+/// # Safety
+/// The files must not be modified or truncated for as long as the returned
+/// archives are alive. See [`SharedMmap`] for details.
+///
+/// # Example
+/// ```
+/// use rkyv::rancor::Error;
+/// use rkyv_util::mmap::{load_dir, OnInvalid};
+///
+/// #[derive(rkyv::Archive, rkyv::Serialize)]
+/// pub struct Test {
+///     hello: u8,
+/// }
+///
+/// let dir = std::env::temp_dir().join("rkyv_util_load_dir_doc");
+/// std::fs::create_dir_all(&dir).unwrap();
+/// let bytes = rkyv::to_bytes::<Error>(&Test { hello: 2 }).unwrap();
+/// std::fs::write(dir.join("test.rkyv"), &bytes).unwrap();
+///
+/// // SAFETY: Nothing else modifies the files while they are mapped.
+/// let archives =
+///     unsafe { load_dir::<Test, Error>(&dir, OnInvalid::Fail) }.unwrap();
+/// assert_eq!(archives[0].1.hello, 2);
+/// # drop(archives);
+/// # std::fs::remove_dir_all(&dir).unwrap();
+/// ```
+pub unsafe fn load_dir<T, E>(
+    dir: impl AsRef<Path>,
+    on_invalid: OnInvalid,
+) -> Result<Vec<(PathBuf, MmapArchive<T>)>, E>
+where
+    T: Archive,
+    T::Archived: ValidateArchive<E>,
+    E: Source,
+{
+    let mut paths = Vec::new();
+    for entry in fs::read_dir(dir).map_err(E::new)? {
+        let entry = entry.map_err(E::new)?;
+        if entry.file_type().map_err(E::new)?.is_file() {
+            paths.push(entry.path());
+        }
+    }
+    paths.sort();
+
+    let mut archives = Vec::with_capacity(paths.len());
+    for path in paths {
+        let file = File::open(&path).map_err(E::new)?;
+        // # Safety
+        // The caller guarantees that the file is not modified while mapped.
+        let map = unsafe { Mmap::map(&file) }.map_err(E::new)?;
+        let len = map.len();
+        match OwnedArchive::from_shared_mmap::<E>(Arc::new(map), 0..len) {
+            Ok(archive) => archives.push((path, archive)),
+            Err(_) if on_invalid == OnInvalid::Skip => (),
+            Err(error) => return Err(error),
+        }
+    }
+    Ok(archives)
+}
+
+/// A memory-mapped archive that can pick up data appended to its file.
+///
+/// This is for tailing an archive log, where each append ends with a new
+/// root. A mapping never grows on its own, so [`refresh`](Self::refresh)
+/// remaps the file when it has grown and validates the new root.
+///
+/// # Example
+/// ```
+/// use std::{fs::OpenOptions, io::Write};
+///
+/// use rkyv::rancor::Error;
+/// use rkyv_util::mmap::GrowingMmapArchive;
+///
+/// #[derive(rkyv::Archive, rkyv::Serialize)]
+/// pub struct Test {
+///     hello: u8,
+/// }
+///
+/// let path = std::env::temp_dir().join("rkyv_util_growing_mmap_doc");
+/// let bytes = rkyv::to_bytes::<Error>(&Test { hello: 2 }).unwrap();
+/// std::fs::write(&path, &bytes).unwrap();
+///
+/// // SAFETY: The file is only ever appended to while it is mapped.
+/// let mut archive =
+///     unsafe { GrowingMmapArchive::<Test>::open::<Error>(&path) }.unwrap();
+/// assert_eq!(archive.hello, 2);
+///
+/// let bytes = rkyv::to_bytes::<Error>(&Test { hello: 3 }).unwrap();
+/// let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+/// file.write_all(&bytes).unwrap();
+///
+/// assert!(archive.refresh::<Error>().unwrap());
+/// assert_eq!(archive.hello, 3);
+/// # drop(archive);
+/// # std::fs::remove_file(&path).unwrap();
+/// ```
+pub struct GrowingMmapArchive<T> {
+    /// The file backing the mapping, kept open to check its length.
+    file: File,
+    /// The archive over the most recent mapping.
+    archive: MmapArchive<T>,
+}
+
+impl<T> GrowingMmapArchive<T> {
+    /// Maps and validates the file at `path` as an archive of `T`.
+    ///
+    /// # Safety
+    /// The file may be appended to, but its existing bytes must not be
+    /// modified or truncated for as long as the archive is alive. Appended
+    /// bytes must not be modified after they have been written either. See
+    /// [`SharedMmap`] for details.
+    pub unsafe fn open<E>(path: impl AsRef<Path>) -> Result<Self, E>
+    where
+        T: Archive,
+        T::Archived: ValidateArchive<E>,
+        E: Source,
+    {
+        let file = File::open(path).map_err(E::new)?;
+        // # Safety
+        // The caller guarantees that the mapped bytes are not modified.
+        let archive = unsafe { map_file::<T, E>(&file) }?;
+        Ok(Self { file, archive })
+    }
+
+    /// Remaps the file if it has grown since it was last mapped.
+    ///
+    /// Returns whether the archive was remapped. If the grown file fails
+    /// validation, for example because an append is still in progress, the
+    /// error is returned and the current archive is kept.
+    pub fn refresh<E>(&mut self) -> Result<bool, E>
+    where
+        T: Archive,
+        T::Archived: ValidateArchive<E>,
+        E: Source,
+    {
+        let len = self.file.metadata().map_err(E::new)?.len();
+        if len <= self.archive.len() as u64 {
+            return Ok(false);
+        }
+
+        // # Safety
+        // The caller of `open` guaranteed that the mapped bytes are not
+        // modified.
+        self.archive = unsafe { map_file::<T, E>(&self.file) }?;
+        Ok(true)
+    }
+
+    /// Gets the archive over the most recent mapping.
+    pub fn archive(&self) -> &MmapArchive<T> {
+        &self.archive
+    }
+}
+
+impl<T: Archive> Deref for GrowingMmapArchive<T> {
+    type Target = T::Archived;
+
+    fn deref(&self) -> &Self::Target {
+        &self.archive
+    }
+}
+
+/// Maps all of `file` and validates it as an archive of `T`.
+///
+/// # Safety
+/// The file must not be modified for as long as the archive is alive.
+unsafe fn map_file<T, E>(file: &File) -> Result<MmapArchive<T>, E>
+where
+    T: Archive,
+    T::Archived: ValidateArchive<E>,
+    E: Source,
+{
+    let map = unsafe { Mmap::map(file) }.map_err(E::new)?;
+    let len = map.len();
+    OwnedArchive::from_shared_mmap::<E>(Arc::new(map), 0..len)
+}
+
+/// An archive that was either memory-mapped or read into memory.
+///
+/// This is returned by [`load_best_effort`], and dereferences to the archived
+/// root either way.
+pub enum BackedArchive<T> {
+    /// The file was memory-mapped.
+    Mapped(MmapArchive<T>),
+    /// The file could not be mapped, so it was read into memory instead.
+    Buffered(OwnedArchive<T, AlignedVec>),
+}
+
+impl<T: Archive> Deref for BackedArchive<T> {
+    type Target = T::Archived;
+
+    fn deref(&self) -> &Self::Target {
+        match self {
+            Self::Mapped(archive) => archive,
+            Self::Buffered(archive) => archive,
+        }
+    }
+}
+
+impl<T: Archive> fmt::Debug for BackedArchive<T>
+where
+    T::Archived: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Mapped(archive) => {
+                f.debug_tuple("Mapped").field(archive).finish()
+            }
+            Self::Buffered(archive) => {
+                f.debug_tuple("Buffered").field(archive).finish()
+            }
+        }
+    }
+}
+
+/// Loads the file at `path` as an archive of `T`, memory-mapping it if
+/// possible and reading it into memory otherwise.
+///
+/// Mapping can fail on some filesystems and for special files; those are
+/// read into an `AlignedVec` instead. Errors opening or reading the file and
+/// validation errors are returned either way.
+///
+/// # Safety
+/// If the file is mapped, it must not be modified or truncated for as long
+/// as the returned archive is alive. See [`SharedMmap`] for details.
+///
+/// # Example
+/// ```
+/// use rkyv::rancor::Error;
+/// use rkyv_util::mmap::load_best_effort;
+///
+/// #[derive(rkyv::Archive, rkyv::Serialize)]
+/// pub struct Test {
+///     hello: u8,
+/// }
+///
+/// let path = std::env::temp_dir().join("rkyv_util_load_best_effort_doc");
+/// let bytes = rkyv::to_bytes::<Error>(&Test { hello: 2 }).unwrap();
+/// std::fs::write(&path, &bytes).unwrap();
+///
+/// // SAFETY: Nothing else modifies the file while it is mapped.
+/// let archive = unsafe { load_best_effort::<Test, Error>(&path) }.unwrap();
+/// assert_eq!(archive.hello, 2);
+/// # drop(archive);
+/// # std::fs::remove_file(&path).unwrap();
+/// ```
+pub unsafe fn load_best_effort<T, E>(
+    path: impl AsRef<Path>,
+) -> Result<BackedArchive<T>, E>
+where
+    T: Archive,
+    T::Archived: ValidateArchive<E>,
+    E: Source,
+{
+    // # Safety
+    // The caller guarantees that the file is not modified while mapped.
+    load_with(path.as_ref(), |file| unsafe { Mmap::map(file) })
+}
+
+fn load_with<T, E>(
+    path: &Path,
+    map: impl FnOnce(&File) -> io::Result<Mmap>,
+) -> Result<BackedArchive<T>, E>
+where
+    T: Archive,
+    T::Archived: ValidateArchive<E>,
+    E: Source,
+{
+    let mut file = File::open(path).map_err(E::new)?;
+    match map(&file) {
+        Ok(map) => {
+            let len = map.len();
+            OwnedArchive::from_shared_mmap::<E>(Arc::new(map), 0..len)
+                .map(BackedArchive::Mapped)
+        }
+        Err(_) => {
+            let mut bytes = AlignedVec::new();
+            bytes.extend_from_reader(&mut file).map_err(E::new)?;
+            OwnedArchive::new::<E>(bytes).map(BackedArchive::Buffered)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs::File, io::Write, sync::Arc};
+
+    use memmap2::Mmap;
+    use rkyv::{munge::munge, rancor, util::AlignedVec, Archive, Serialize};
+
+    use super::{
+        load_best_effort, load_dir, load_with, BackedArchive,
+        GrowingMmapArchive, MmapWriter, OnInvalid, SharedMmap,
+    };
+    use crate::owned::OwnedArchive;
+
+    #[derive(Archive, Serialize)]
+    pub struct Record {
+        id: u32,
+        score: u64,
+    }
+
+    #[test]
+    fn test_shared_mmap_views() {
+        let first =
+            rkyv::to_bytes::<rancor::Error>(&Record { id: 1, score: 10 })
+                .unwrap();
+        let second =
+            rkyv::to_bytes::<rancor::Error>(&Record { id: 2, score: 20 })
+                .unwrap();
+
+        // Lay both archives out in one file, keeping the second one aligned.
+        let offset = first.len().next_multiple_of(16);
+        let mut contents = first.to_vec();
+        contents.resize(offset, 0);
+        contents.extend_from_slice(&second);
+
+        let path = std::env::temp_dir()
+            .join(format!("rkyv_util_test_shared_mmap_{}", std::process::id()));
+        File::create(&path).unwrap().write_all(&contents).unwrap();
+
+        let file = File::open(&path).unwrap();
+        let map = Arc::new(unsafe { Mmap::map(&file) }.unwrap());
+
+        let a = OwnedArchive::<Record, SharedMmap>::from_shared_mmap::<
+            rancor::Error,
+        >(map.clone(), 0..first.len())
+        .unwrap();
+        let b = OwnedArchive::<Record, SharedMmap>::from_shared_mmap::<
+            rancor::Error,
+        >(map.clone(), offset..offset + second.len())
+        .unwrap();
+
+        assert_eq!((a.id.to_native(), a.score.to_native()), (1, 10));
+        assert_eq!((b.id.to_native(), b.score.to_native()), (2, 20));
+        // One mapping is shared between the test and both views.
+        assert_eq!(Arc::strong_count(&map), 3);
+
+        let out_of_bounds = SharedMmap::new(map, 0..contents.len() + 1);
+        assert!(out_of_bounds.is_none());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_shared_mmap_directory_entry() {
+        // A batch file of aligned entries, followed by a directory of the
+        // `(offset, len)` of each entry as its own archive.
+        let mut contents = Vec::new();
+        let mut directory = Vec::new();
+        for id in 0..3 {
+            let record = Record {
+                id,
+                score: u64::from(id) * 10,
+            };
+            let bytes = rkyv::to_bytes::<rancor::Error>(&record).unwrap();
+            contents.resize(contents.len().next_multiple_of(16), 0);
+            directory.push((contents.len() as u64, bytes.len() as u64));
+            contents.extend_from_slice(&bytes);
+        }
+        let directory_bytes =
+            rkyv::to_bytes::<rancor::Error>(&directory).unwrap();
+        contents.resize(contents.len().next_multiple_of(16), 0);
+        let directory_offset = contents.len();
+        contents.extend_from_slice(&directory_bytes);
+
+        let path = std::env::temp_dir().join(format!(
+            "rkyv_util_test_shared_mmap_directory_{}",
+            std::process::id()
+        ));
+        File::create(&path).unwrap().write_all(&contents).unwrap();
+
+        let file = File::open(&path).unwrap();
+        let map = Arc::new(unsafe { Mmap::map(&file) }.unwrap());
+
+        let directory =
+            OwnedArchive::<Vec<(u64, u64)>, SharedMmap>::from_shared_mmap::<
+                rancor::Error,
+            >(map.clone(), directory_offset..contents.len())
+            .unwrap();
+        let offset = directory[2].0.to_native() as usize;
+        let len = directory[2].1.to_native() as usize;
+
+        let entry = OwnedArchive::<Record, SharedMmap>::from_shared_mmap::<
+            rancor::Error,
+        >(map.clone(), offset..offset + len)
+        .unwrap();
+        assert_eq!((entry.id.to_native(), entry.score.to_native()), (2, 20));
+        assert!(!entry.is_mutable());
+
+        // A range past the end of the file is an error, not a panic.
+        let past_end = OwnedArchive::<Record, SharedMmap>::from_shared_mmap::<
+            rancor::Error,
+        >(map, offset..contents.len() + 1);
+        assert!(past_end.is_err());
+
+        drop((directory, entry));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_shared_mmap_clone_into_container() {
+        let bytes =
+            rkyv::to_bytes::<rancor::Error>(&Record { id: 3, score: 30 })
+                .unwrap();
+
+        let path = std::env::temp_dir().join(format!(
+            "rkyv_util_test_shared_mmap_clone_{}",
+            std::process::id()
+        ));
+        File::create(&path).unwrap().write_all(&bytes).unwrap();
+
+        let file = File::open(&path).unwrap();
+        let map = Arc::new(unsafe { Mmap::map(&file) }.unwrap());
+        let mapped = OwnedArchive::<Record, SharedMmap>::from_shared_mmap::<
+            rancor::Error,
+        >(map, 0..bytes.len())
+        .unwrap();
+
+        let copy = mapped
+            .clone_into_container::<AlignedVec, rancor::Error>()
+            .unwrap();
+        drop(mapped);
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!((copy.id.to_native(), copy.score.to_native()), (3, 30));
+    }
+
+    #[test]
+    fn test_load_dir() {
+        let dir = std::env::temp_dir()
+            .join(format!("rkyv_util_test_load_dir_{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("nested")).unwrap();
+        for id in 0..3 {
+            let bytes = rkyv::to_bytes::<rancor::Error>(&Record {
+                id,
+                score: u64::from(id) * 10,
+            })
+            .unwrap();
+            std::fs::write(dir.join(format!("{id}.rkyv")), &bytes).unwrap();
+        }
+        std::fs::write(dir.join("invalid.rkyv"), [1, 2, 3]).unwrap();
+
+        let archives =
+            unsafe { load_dir::<Record, rancor::Error>(&dir, OnInvalid::Skip) }
+                .unwrap();
+        let ids = archives
+            .iter()
+            .map(|(_, archive)| archive.id.to_native())
+            .collect::<Vec<_>>();
+        assert_eq!(ids, [0, 1, 2]);
+        assert!(archives[2].0.ends_with("2.rkyv"));
+        drop(archives);
+
+        let failed =
+            unsafe { load_dir::<Record, rancor::Error>(&dir, OnInvalid::Fail) };
+        assert!(failed.is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_load_best_effort_fallback() {
+        let bytes =
+            rkyv::to_bytes::<rancor::Error>(&Record { id: 4, score: 40 })
+                .unwrap();
+        let path = std::env::temp_dir().join(format!(
+            "rkyv_util_test_load_best_effort_{}",
+            std::process::id()
+        ));
+        std::fs::write(&path, &bytes).unwrap();
+
+        let mapped =
+            unsafe { load_best_effort::<Record, rancor::Error>(&path) }
+                .unwrap();
+        assert!(matches!(mapped, BackedArchive::Mapped(_)));
+        assert_eq!(mapped.id, 4);
+        drop(mapped);
+
+        // Simulate a filesystem that can't be mapped.
+        let buffered = load_with::<Record, rancor::Error>(&path, |_| {
+            Err(std::io::Error::other("mapping not supported"))
+        })
+        .unwrap();
+        assert!(matches!(buffered, BackedArchive::Buffered(_)));
+        assert_eq!(buffered.score, 40);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_growing_mmap_archive_refresh() {
+        let first =
+            rkyv::to_bytes::<rancor::Error>(&Record { id: 5, score: 50 })
+                .unwrap();
+        let path = std::env::temp_dir().join(format!(
+            "rkyv_util_test_growing_mmap_{}",
+            std::process::id()
+        ));
+        std::fs::write(&path, &first).unwrap();
+
+        let mut archive = unsafe {
+            GrowingMmapArchive::<Record>::open::<rancor::Error>(&path)
+        }
+        .unwrap();
+        assert_eq!(archive.id, 5);
+        assert!(!archive.refresh::<rancor::Error>().unwrap());
+
+        // Append a second archive at an aligned offset, as another process
+        // writing to the log would.
+        let second =
+            rkyv::to_bytes::<rancor::Error>(&Record { id: 6, score: 60 })
+                .unwrap();
+        let offset = first.len().next_multiple_of(16);
+        let mut file = std::fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap();
+        file.write_all(&vec![0; offset - first.len()]).unwrap();
+        file.write_all(&second).unwrap();
+
+        assert!(archive.refresh::<rancor::Error>().unwrap());
+        assert_eq!(
+            (archive.id.to_native(), archive.score.to_native()),
+            (6, 60)
+        );
+        assert_eq!(archive.archive().len(), offset + second.len());
+        assert!(!archive.refresh::<rancor::Error>().unwrap());
+
+        drop(archive);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_to_mmap_file() {
+        let bytes =
+            rkyv::to_bytes::<rancor::Error>(&Record { id: 1, score: 10 })
+                .unwrap();
+        let owned =
+            OwnedArchive::<Record, _>::new::<rancor::Error>(bytes).unwrap();
+
+        let path = std::env::temp_dir().join(format!(
+            "rkyv_util_test_to_mmap_file_{}",
+            std::process::id()
+        ));
+        let mut mapped =
+            unsafe { owned.to_mmap_file::<rancor::Error>(&path) }.unwrap();
+        assert_eq!(mapped.id, 1);
+        assert_eq!(mapped.score, 10);
+        assert_eq!(std::fs::read(&path).unwrap(), owned.as_bytes());
+
+        // Edits go to the file once the mapping is flushed.
+        munge!(let ArchivedRecord { mut score, .. } = mapped.get_mut());
+        *score = 20.into();
+        munge!(
+            let ArchivedRecord { mut id, .. } = mapped.try_get_mut().unwrap()
+        );
+        *id = 2.into();
+        mapped.container().flush().unwrap();
+        drop(mapped);
+
+        let mut aligned = AlignedVec::<16>::new();
+        aligned.extend_from_slice(&std::fs::read(&path).unwrap());
+        let reread =
+            OwnedArchive::<Record, _>::new::<rancor::Error>(aligned).unwrap();
+        assert_eq!((reread.id.to_native(), reread.score.to_native()), (2, 20));
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_mmap_writer_grows_and_truncates() {
+        #[derive(Archive, Serialize)]
+        pub struct Log {
+            records: Vec<Record>,
+        }
+
+        let log = Log {
+            records: (0..100)
+                .map(|id| Record {
+                    id,
+                    score: u64::from(id) * 10,
+                })
+                .collect(),
+        };
+        let expected = rkyv::to_bytes::<rancor::Error>(&log).unwrap();
+
+        let path = std::env::temp_dir()
+            .join(format!("rkyv_util_test_mmap_writer_{}", std::process::id()));
+        let file = std::fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(&path)
+            .unwrap();
+
+        // Start far too small, so the file has to grow a few times.
+        let writer = unsafe { MmapWriter::new(file, 16) }.unwrap();
+        let archive = writer.serialize::<Log, rancor::Error>(&log).unwrap();
+        assert_eq!(archive.records.len(), 100);
+        assert_eq!(archive.records[99].id, 99);
+        assert_eq!(archive.as_bytes(), expected.as_slice());
+        drop(archive);
+
+        // The file holds exactly the archive, and reads back.
+        let bytes = std::fs::read(&path).unwrap();
+        assert_eq!(bytes.len(), expected.len());
+        let mut aligned = AlignedVec::<16>::new();
+        aligned.extend_from_slice(&bytes);
+        let reread =
+            OwnedArchive::<Log, _>::new::<rancor::Error>(aligned).unwrap();
+        assert_eq!(reread.records[42].score, 420);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -4,14 +4,37 @@
 //! we want to pass Archives around in channels but we do not want
 //! to deal with complicated lifetimes.
 
-use core::fmt::Debug;
-use std::{marker::PhantomData, ops::Deref, rc::Rc, sync::Arc};
+use core::{
+    fmt::Debug,
+    hash::{Hash, Hasher},
+    num::NonZeroUsize,
+};
+use std::{
+    borrow::Borrow,
+    collections::{BTreeMap, HashMap},
+    error::Error,
+    io::{self, Read},
+    marker::PhantomData,
+    ops::{Deref, Range},
+    rc::Rc,
+    sync::Arc,
+};
 
 use rkyv::{
-    api::high::HighValidator, bytecheck::CheckBytes, seal::Seal,
-    util::AlignedVec, Archive, Portable,
+    api::high::HighDeserializer,
+    de::Pool,
+    rancor::BoxedError,
+    seal::Seal,
+    util::AlignedVec,
+    validation::{
+        archive::ArchiveValidator, shared::SharedValidator, Validator,
+    },
+    vec::ArchivedVec,
+    Archive, Deserialize, Portable,
 };
 
+use crate::compat::ValidateArchive;
+
 /// An owned archive type.
 ///
 /// This requires a container that implements the `StableBytes`
@@ -46,137 +69,2467 @@ pub struct OwnedArchive<T, C> {
     _type: PhantomData<T>,
 }
 
+/// An archive that mutably borrows its bytes from a `Vec<u8>`.
+///
+/// This suits edit sessions where the caller keeps ownership of a scratch
+/// `Vec`: the archive can edit the bytes in place for as long as the borrow
+/// lasts, after which the `Vec` and its edited contents are available again.
+/// Unlike an archive that owns its `Vec<u8>`, this can't outlive the `Vec`.
+///
+/// # Example
+/// ```
+/// use rkyv::{munge::munge, rancor::Error};
+/// use rkyv_util::owned::BorrowedMutArchive;
+///
+/// #[derive(rkyv::Archive, rkyv::Serialize)]
+/// pub struct Test {
+///     hello: u8,
+/// }
+///
+/// let mut scratch = rkyv::to_bytes::<Error>(&Test { hello: 2 })
+///     .unwrap()
+///     .to_vec();
+///
+/// let mut archive =
+///     BorrowedMutArchive::<Test>::new::<Error>(&mut scratch).unwrap();
+/// munge!(let ArchivedTest { mut hello } = archive.get_mut());
+/// *hello = 3;
+///
+/// assert_eq!(scratch, [3]);
+/// ```
+pub type BorrowedMutArchive<'a, T> = OwnedArchive<T, &'a mut Vec<u8>>;
+
+/// A read-only archive that borrows its bytes.
+///
+/// This is `Copy`, so it can be handed to functions that should neither own
+/// nor mutate an archive. See [`OwnedArchive::reborrow`].
+pub type BorrowedArchive<'a, T> = OwnedArchive<T, &'a [u8]>;
+
+/// Limits applied while validating an archive.
+///
+/// The default places no limits on validation.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ValidationLimits {
+    /// The maximum depth of nested objects to validate, or `None` for no
+    /// limit.
+    pub max_depth: Option<NonZeroUsize>,
+}
+
+/// Details about an archive that was successfully validated.
+///
+/// This is returned by [`OwnedArchive::new_reported`] for callers that want
+/// to record metrics about the archives they load.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ValidationReport {
+    /// The length of the whole buffer, in bytes.
+    pub total_len: usize,
+    /// The size of the archived root object, in bytes.
+    pub archived_size: usize,
+    /// The position of the root object within the buffer.
+    ///
+    /// Archives are accessed with their root at the very end of the buffer,
+    /// so this is always `total_len - archived_size`.
+    pub root_pos: usize,
+}
+
+/// A problem found by [`diagnose`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ValidationIssue {
+    /// The buffer is too short to hold the root object.
+    TooShort {
+        /// The length of the buffer.
+        len: usize,
+        /// The size of the root object.
+        needed: usize,
+    },
+    /// The root object is not suitably aligned.
+    Unaligned {
+        /// The alignment the root needs.
+        align: usize,
+    },
+    /// rkyv's validator rejected the contents of the archive.
+    Invalid {
+        /// The validator's error, including the path to the bad value.
+        message: String,
+    },
+}
+
+impl core::fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::TooShort { len, needed } => write!(
+                f,
+                "buffer of {len} bytes is too short for a root of {needed} \
+                 bytes",
+            ),
+            Self::Unaligned { align } => {
+                write!(f, "root is not aligned to {align} bytes")
+            }
+            Self::Invalid { message } => f.write_str(message),
+        }
+    }
+}
+
+impl Error for ValidationIssue {}
+
+/// Checks that `bytes` can hold a `U` at its very end, where archives keep
+/// their root, and that the root is aligned there.
+///
+/// This is the constant-time part of validation, shared by [`diagnose`],
+/// validating constructors and `ShallowArchive`.
+pub(crate) fn check_root<U>(bytes: &[u8]) -> Result<(), ValidationIssue> {
+    let needed = size_of::<U>();
+    let Some(root_pos) = bytes.len().checked_sub(needed) else {
+        return Err(ValidationIssue::TooShort {
+            len: bytes.len(),
+            needed,
+        });
+    };
+    if !bytes[root_pos..].as_ptr().cast::<U>().is_aligned() {
+        return Err(ValidationIssue::Unaligned {
+            align: align_of::<U>(),
+        });
+    }
+    Ok(())
+}
+
+/// Checks `bytes` as an archive of `T`, collecting what is wrong with them.
+///
+/// This is for tools that report on corrupt archives rather than load them.
+/// An empty result means the bytes would pass [`OwnedArchive::new`].
+///
+/// rkyv's validator stops at the first bad value, so at most one
+/// [`ValidationIssue::Invalid`] is reported. Its message is kept in release
+/// builds too. A misaligned buffer is copied into one whose start is aligned
+/// for the root, so that its contents can still be checked. If the root is
+/// still misaligned in the copy, because the buffer starts with bytes that
+/// are not part of the archive, it is only reported as
+/// [`ValidationIssue::Unaligned`].
+///
+/// # Example
+/// ```
+/// use rkyv::rancor::Error;
+/// use rkyv_util::owned::{diagnose, ValidationIssue};
+///
+/// #[derive(rkyv::Archive, rkyv::Serialize)]
+/// pub struct Test {
+///     hello: bool,
+/// }
+///
+/// let mut bytes = rkyv::to_bytes::<Error>(&Test { hello: true }).unwrap();
+/// assert!(diagnose::<Test>(&bytes).is_empty());
+///
+/// bytes[0] = 2;
+/// let issues = diagnose::<Test>(&bytes);
+/// assert!(matches!(issues[..], [ValidationIssue::Invalid { .. }]));
+/// ```
+pub fn diagnose<T>(bytes: &[u8]) -> Vec<ValidationIssue>
+where
+    T: Archive,
+    T::Archived: ValidateArchive<BoxedError>,
+{
+    let mut issues = Vec::new();
+    let mut copy = Vec::new();
+    let mut contents = bytes;
+    match check_root::<T::Archived>(bytes) {
+        Ok(()) => (),
+        Err(issue @ ValidationIssue::Unaligned { align }) => {
+            issues.push(issue);
+            // Start the copy at least as aligned as an `AlignedVec`, and as
+            // aligned as the root needs.
+            let align = align.max(AlignedVec::<16>::ALIGNMENT);
+            copy.resize(bytes.len() + align, 0);
+            let start = copy.as_ptr().align_offset(align);
+            copy[start..start + bytes.len()].copy_from_slice(bytes);
+            contents = &copy[start..start + bytes.len()];
+            if check_root::<T::Archived>(contents).is_err() {
+                // The root is offset within the buffer, so its contents
+                // can't be checked without repeating the issue.
+                return issues;
+            }
+        }
+        Err(issue) => return vec![issue],
+    }
+
+    // `BoxedError` keeps the validator's message in release builds, unlike
+    // `rancor::Error`.
+    if let Err(error) = validate_root::<T::Archived, BoxedError>(contents, None)
+    {
+        issues.push(ValidationIssue::Invalid {
+            message: error.to_string(),
+        });
+    }
+    issues
+}
+
+/// Computes a checksum of `bytes` one `chunk_size` chunk at a time.
+///
+/// Each chunk is hashed with XXH3 and the chunk hashes are hashed together,
+/// so the result differs from [`OwnedArchive::checksum`]. It is the same
+/// value that `par_chunked_checksum` computes in parallel with the `rayon`
+/// feature, which makes it the sequential fallback for checksums recorded by
+/// it.
+///
+/// # Panics
+/// Panics if `chunk_size` is 0.
+///
+/// # Example
+/// ```
+/// use rkyv_util::owned::chunked_checksum;
+///
+/// let bytes = vec![7; 1000];
+/// assert_eq!(chunked_checksum(&bytes, 64), chunked_checksum(&bytes, 64));
+/// assert_ne!(chunked_checksum(&bytes, 64), chunked_checksum(&bytes, 32));
+/// ```
+#[cfg(feature = "checksum")]
+pub fn chunked_checksum(bytes: &[u8], chunk_size: usize) -> u64 {
+    let hashes = bytes
+        .chunks(chunk_size)
+        .map(xxhash_rust::xxh3::xxh3_64)
+        .collect::<Vec<_>>();
+    combine_chunk_hashes(&hashes)
+}
+
+/// Computes the same checksum as [`chunked_checksum`], hashing the chunks in
+/// parallel with rayon.
+///
+/// This is for checking the integrity of archives of several gigabytes
+/// before validating them, which has to walk the archive sequentially. Hashing
+/// uses every core rayon has, so pick a `chunk_size` that leaves each of them
+/// a few megabytes of work.
+///
+/// # Panics
+/// Panics if `chunk_size` is 0.
+///
+/// # Example
+/// ```
+/// use rkyv_util::owned::{chunked_checksum, par_chunked_checksum};
+///
+/// let bytes = vec![7; 1000];
+/// assert_eq!(
+///     par_chunked_checksum(&bytes, 64),
+///     chunked_checksum(&bytes, 64),
+/// );
+/// ```
+#[cfg(feature = "rayon")]
+pub fn par_chunked_checksum(bytes: &[u8], chunk_size: usize) -> u64 {
+    use rayon::{iter::ParallelIterator, slice::ParallelSlice};
+
+    let hashes = bytes
+        .par_chunks(chunk_size)
+        .map(xxhash_rust::xxh3::xxh3_64)
+        .collect::<Vec<_>>();
+    combine_chunk_hashes(&hashes)
+}
+
+/// Hashes the chunk hashes of a chunked checksum together, in order.
+#[cfg(feature = "checksum")]
+fn combine_chunk_hashes(hashes: &[u64]) -> u64 {
+    let mut hasher = xxhash_rust::xxh3::Xxh3::new();
+    for hash in hashes {
+        hasher.update(&hash.to_le_bytes());
+    }
+    hasher.digest()
+}
+
 impl<T, C> OwnedArchive<T, C> {
     /// Creates a new `OwnedArchive` from a container
     /// that supports the `StableBytes` interface.
+    ///
+    /// # Alignment
+    /// Archived types usually need their bytes to be aligned, but containers
+    /// like `Vec<u8>`, `Box<[u8]>` and `Arc<[u8]>` only guarantee an alignment
+    /// of 1. Validation checks the alignment of every object in the archive,
+    /// so a container that happens to be misaligned is rejected with an error
+    /// here instead of being accessed unsoundly later. Whether a given
+    /// `Vec<u8>` is aligned is up to the allocator, so prefer `AlignedVec`
+    /// whenever the archive contains anything with an alignment above 1.
+    ///
+    /// A buffer whose root, which sits at its very end, is not aligned for
+    /// the root type is rejected before validation, with an error giving the
+    /// alignment it needs. [`try_new`](Self::try_new) and
+    /// [`new_with_limits`](Self::new_with_limits) check the same way.
+    /// Archives serialized for a larger alignment than `AlignedVec`'s can be
+    /// copied into a suitable buffer with
+    /// [`new_aligned_to`](OwnedArchive::new_aligned_to).
+    ///
+    /// # Tracing
+    /// With the `tracing` feature enabled, validation runs in a
+    /// `validate_archive` span recording the type name and byte length, and
+    /// a failure is reported as an error event.
     pub fn new<E>(container: C) -> Result<Self, E>
     where
         T: Archive,
-        T::Archived: Portable + for<'a> CheckBytes<HighValidator<'a, E>>,
+        T::Archived: ValidateArchive<E>,
         E: rkyv::rancor::Source,
         C: StableBytes,
     {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!(
+            "validate_archive",
+            type_name = core::any::type_name::<T>(),
+            len = container.bytes().len(),
+        )
+        .entered();
+
         // Here we check if the bytes are good. If so, we will
         // allow for the creation of the `OwnedArchive`.
-        rkyv::access::<T::Archived, E>(container.bytes())?;
+        let bytes = container.bytes();
+        let result = validate_root::<T::Archived, E>(bytes, None);
+
+        #[cfg(feature = "tracing")]
+        match &result {
+            Ok(_) => tracing::debug!("archive validated"),
+            Err(error) => {
+                tracing::error!(%error, "archive failed validation");
+            }
+        }
+
+        result?;
 
         Ok(Self {
             container,
             _type: PhantomData,
         })
     }
-    /// Gets the pinned object as mutable.
+
+    /// Creates a new `OwnedArchive`, handing the container back if
+    /// validation fails.
+    ///
+    /// This is like [`new`](Self::new), but lets the caller retry, inspect or
+    /// reuse the bytes of a container that is expensive to reacquire.
     ///
     /// # Example
     /// ```
-    /// use rkyv::{rancor::Error, util::AlignedVec, munge::munge};
+    /// use rkyv::rancor::Error;
     /// use rkyv_util::owned::OwnedArchive;
     ///
     /// #[derive(rkyv::Archive, rkyv::Serialize)]
     /// pub struct Test {
-    ///     hello: u8,
+    ///     hello: bool,
     /// }
     ///
-    /// let bytes = rkyv::to_bytes::<Error>(&Test { hello: 2 }).unwrap();
+    /// let mut bytes =
+    ///     rkyv::to_bytes::<Error>(&Test { hello: true }).unwrap();
+    /// bytes[0] = 2;
     ///
+    /// let result = OwnedArchive::<Test, _>::try_new::<Error>(bytes);
+    /// let Err((mut bytes, _)) = result else {
+    ///     panic!("an invalid bool passed validation");
+    /// };
+    /// bytes[0] = 0;
     /// let owned_archive =
-    ///     &mut OwnedArchive::<Test, _>::new::<Error>(bytes).unwrap();
-    /// assert_eq!(owned_archive.hello, 2);
+    ///     OwnedArchive::<Test, _>::new::<Error>(bytes).unwrap();
+    /// assert!(!owned_archive.hello);
+    /// ```
+    pub fn try_new<E>(container: C) -> Result<Self, (C, E)>
+    where
+        T: Archive,
+        T::Archived: ValidateArchive<E>,
+        E: rkyv::rancor::Source,
+        C: StableBytes,
+    {
+        match validate_root::<T::Archived, E>(container.bytes(), None) {
+            Ok(()) => Ok(Self {
+                container,
+                _type: PhantomData,
+            }),
+            Err(error) => Err((container, error)),
+        }
+    }
+
+    /// Validates `container` and swaps it in, returning the old container.
     ///
-    /// munge!(let ArchivedTest { mut hello, ..} = owned_archive.get_mut());
-    /// *hello = 3;
-    /// // `hello` should be 3 back in the owned archive.
-    /// assert_eq!(*hello, 3);
+    /// If validation fails, the archive is left unchanged and `container` is
+    /// handed back with the error, as with [`try_new`](Self::try_new).
+    ///
+    /// # Example
+    /// ```
+    /// use rkyv::rancor::Error;
+    /// use rkyv_util::owned::OwnedArchive;
+    ///
+    /// #[derive(rkyv::Archive, rkyv::Serialize)]
+    /// pub struct Test {
+    ///     hello: u8,
+    /// }
+    ///
+    /// let bytes = rkyv::to_bytes::<Error>(&Test { hello: 2 }).unwrap();
+    /// let mut owned_archive =
+    ///     OwnedArchive::<Test, _>::new::<Error>(bytes).unwrap();
+    ///
+    /// let bytes = rkyv::to_bytes::<Error>(&Test { hello: 3 }).unwrap();
+    /// let old = owned_archive.replace_with::<Error>(bytes).unwrap();
     /// assert_eq!(owned_archive.hello, 3);
+    /// assert_eq!(old[0], 2);
     /// ```
-    pub fn get_mut(&mut self) -> Seal<T::Archived>
+    pub fn replace_with<E>(&mut self, container: C) -> Result<C, (C, E)>
     where
         T: Archive,
-        T::Archived: Portable,
-        C: StableBytesMut,
+        T::Archived: ValidateArchive<E>,
+        E: rkyv::rancor::Source,
+        C: StableBytes,
     {
-        // # Safety
-        // Here we can safely access the underlying archive. This is
-        // because `StableBytesMut` enforces the safety contract that the
-        // underlying bytes remain stable, and thus the container that
-        // we took ownership of when creating the `OwnedArchive` has
-        // already been created.
-        unsafe {
-            rkyv::access_unchecked_mut::<T::Archived>(
-                self.container.bytes_mut(),
-            )
-        }
+        let archive = Self::try_new::<E>(container)?;
+        Ok(core::mem::replace(self, archive).container)
     }
-}
 
-impl<C: StableBytes, T: Archive> Deref for OwnedArchive<T, C> {
-    type Target = T::Archived;
+    /// Creates a new `OwnedArchive` for a root that is plain old data.
+    ///
+    /// When the archived root contains no pointers and every bit pattern is a
+    /// valid value of it, which `bytemuck::AnyBitPattern` promises, the only
+    /// things that can be wrong with the bytes are their size and alignment.
+    /// Those are checked in constant time, skipping rkyv's validation
+    /// entirely. The buffer must be exactly the size of the root.
+    ///
+    /// # Example
+    /// ```
+    /// use bytemuck::AnyBitPattern;
+    /// use rkyv::rancor::Error;
+    /// use rkyv_util::owned::OwnedArchive;
+    ///
+    /// #[derive(rkyv::Archive, rkyv::Serialize)]
+    /// #[rkyv(derive(Clone, Copy, AnyBitPattern))]
+    /// pub struct Pixel {
+    ///     rgb: [u8; 3],
+    /// }
+    ///
+    /// let bytes = rkyv::to_bytes::<Error>(&Pixel { rgb: [1, 2, 3] }).unwrap();
+    /// let owned_archive =
+    ///     OwnedArchive::<Pixel, _>::new_pod::<Error>(bytes).unwrap();
+    /// assert_eq!(owned_archive.rgb, [1, 2, 3]);
+    /// ```
+    #[cfg(feature = "bytemuck")]
+    pub fn new_pod<E>(container: C) -> Result<Self, E>
+    where
+        T: Archive,
+        T::Archived: bytemuck::AnyBitPattern,
+        E: rkyv::rancor::Source,
+        C: StableBytes,
+    {
+        bytemuck::try_from_bytes::<T::Archived>(container.bytes())
+            .map_err(E::new)?;
 
-    fn deref(&self) -> &Self::Target {
-        // # Safety
-        // Here we can safely access the underlying archive. This is
-        // because `StableBytes` enforces the safety contract that the
-        // underlying bytes remain stable, and thus the container that
-        // we took ownership of when creating the `OwnedArchive` has
-        // already been created.
-        unsafe { rkyv::access_unchecked(self.container.bytes()) }
+        Ok(Self {
+            container,
+            _type: PhantomData,
+        })
     }
-}
 
-impl<T, C: Clone> Clone for OwnedArchive<T, C> {
-    fn clone(&self) -> Self {
-        Self {
-            container: self.container.clone(),
-            _type: self._type,
-        }
+    /// Creates a new `OwnedArchive`, validating under the given limits.
+    ///
+    /// Validation recurses once for every level of nesting in the archive,
+    /// so a maliciously deep archive can exhaust the stack. Setting
+    /// [`ValidationLimits::max_depth`] makes such archives fail validation
+    /// instead.
+    ///
+    /// # Example
+    /// ```
+    /// use core::num::NonZeroUsize;
+    ///
+    /// use rkyv::rancor::Error;
+    /// use rkyv_util::owned::{OwnedArchive, ValidationLimits};
+    ///
+    /// #[derive(rkyv::Archive, rkyv::Serialize)]
+    /// pub struct Test {
+    ///     hello: Box<u8>,
+    /// }
+    ///
+    /// let bytes =
+    ///     rkyv::to_bytes::<Error>(&Test { hello: Box::new(2) }).unwrap();
+    /// let limits = ValidationLimits {
+    ///     max_depth: NonZeroUsize::new(4),
+    /// };
+    ///
+    /// let owned_archive =
+    ///     OwnedArchive::<Test, _>::new_with_limits::<Error>(bytes, limits)
+    ///         .unwrap();
+    /// assert_eq!(*owned_archive.hello, 2);
+    /// ```
+    pub fn new_with_limits<E>(
+        container: C,
+        limits: ValidationLimits,
+    ) -> Result<Self, E>
+    where
+        T: Archive,
+        T::Archived: ValidateArchive<E>,
+        E: rkyv::rancor::Source,
+        C: StableBytes,
+    {
+        validate_root::<T::Archived, E>(container.bytes(), limits.max_depth)?;
+
+        Ok(Self {
+            container,
+            _type: PhantomData,
+        })
     }
-}
 
-impl<T: Archive, C: StableBytes> Debug for OwnedArchive<T, C>
-where
-    T::Archived: Debug,
-{
-    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        self.deref().fmt(f)
+    /// Creates a new `OwnedArchive` and reports on the layout of the
+    /// validated archive.
+    ///
+    /// # Example
+    /// ```
+    /// use rkyv::rancor::Error;
+    /// use rkyv_util::owned::OwnedArchive;
+    ///
+    /// #[derive(rkyv::Archive, rkyv::Serialize)]
+    /// pub struct Test {
+    ///     hello: u8,
+    /// }
+    ///
+    /// let bytes = rkyv::to_bytes::<Error>(&Test { hello: 2 }).unwrap();
+    /// let (owned_archive, report) =
+    ///     OwnedArchive::<Test, _>::new_reported::<Error>(bytes).unwrap();
+    /// assert_eq!(owned_archive.hello, 2);
+    /// assert_eq!(report.total_len, 1);
+    /// ```
+    pub fn new_reported<E>(container: C) -> Result<(Self, ValidationReport), E>
+    where
+        T: Archive,
+        T::Archived: ValidateArchive<E>,
+        E: rkyv::rancor::Source,
+        C: StableBytes,
+    {
+        let archive = Self::new::<E>(container)?;
+        let total_len = archive.container.bytes().len();
+        let archived_size = core::mem::size_of::<T::Archived>();
+        let report = ValidationReport {
+            total_len,
+            archived_size,
+            // Validation succeeded, so the root fits in the buffer and ends
+            // at its end.
+            root_pos: total_len - archived_size,
+        };
+        Ok((archive, report))
     }
-}
 
-/// A contract guaranteeing that bytes should originate
-/// from the same source between accesses.
-///
-/// # Safety
-/// - `bytes` always refers to the same buffer.
-/// - The **only** way to edit the underlying bytes is through `StableBytesMut`
-///
-/// For instance, the following malicious implementation would be unsafe:
-/// ```
-/// use std::cell::RefCell;
-///
-/// use rkyv_util::owned::StableBytes;
-///
-/// struct Malicious {
-///     counter: RefCell<u8>,
-/// }
-///
-/// unsafe impl StableBytes for Malicious {
-///     fn bytes(&self) -> &[u8] {
-///         *self.counter.borrow_mut() += 1;
-///         if *self.counter.borrow() % 2 == 0 {
-///             &[0x00]
-///         } else {
-///             &[0x01]
-///         }
-///     }
-/// }
-/// ```
-/// The above code does not meet the safety contract because
-/// every other access will return a different set of bytes and it is not
-/// referencing a constant buffer.
-///
-/// Another example for possible unsafety is as follows:
-/// ```
-/// use rkyv_util::owned::StableBytes;
-///
+    /// Creates a new `OwnedArchive` from the writer of a serializer that has
+    /// finished serializing, without copying its bytes.
+    ///
+    /// This supports rkyv's [`Serializer`](rkyv::ser::Serializer) with any
+    /// writer that is also a `StableBytes` container, such as `AlignedVec` or
+    /// `Vec<u8>`. The allocator and sharing state are dropped.
+    ///
+    /// # Example
+    /// ```
+    /// use rkyv::{
+    ///     api::serialize_using,
+    ///     rancor::Error,
+    ///     ser::{sharing::Share, Serializer},
+    ///     util::{with_arena, AlignedVec},
+    /// };
+    /// use rkyv_util::owned::OwnedArchive;
+    ///
+    /// #[derive(rkyv::Archive, rkyv::Serialize)]
+    /// pub struct Test {
+    ///     hello: u8,
+    /// }
+    ///
+    /// let owned_archive = with_arena(|arena| {
+    ///     let writer = AlignedVec::<16>::new();
+    ///     let mut serializer =
+    ///         Serializer::new(writer, arena.acquire(), Share::new());
+    ///     serialize_using::<_, Error>(&Test { hello: 2 }, &mut serializer)
+    ///         .unwrap();
+    ///     OwnedArchive::<Test, _>::from_serializer::<_, _, Error>(serializer)
+    /// })
+    /// .unwrap();
+    /// assert_eq!(owned_archive.hello, 2);
+    /// ```
+    pub fn from_serializer<A, S, E>(
+        serializer: rkyv::ser::Serializer<C, A, S>,
+    ) -> Result<Self, E>
+    where
+        T: Archive,
+        T::Archived: ValidateArchive<E>,
+        E: rkyv::rancor::Source,
+        C: StableBytes,
+    {
+        Self::new::<E>(serializer.into_writer())
+    }
+
+    /// Creates a new `OwnedArchive` after transforming the container's bytes
+    /// in place.
+    ///
+    /// `transform` runs on the bytes through `StableBytesMut` before they are
+    /// validated, which suits reversible transforms like descrambling that
+    /// don't change the length of the buffer. Validation happens on the
+    /// transformed bytes.
+    ///
+    /// # Example
+    /// ```
+    /// use rkyv::rancor::Error;
+    /// use rkyv_util::owned::OwnedArchive;
+    ///
+    /// #[derive(rkyv::Archive, rkyv::Serialize)]
+    /// pub struct Test {
+    ///     hello: u8,
+    /// }
+    ///
+    /// let mut bytes = rkyv::to_bytes::<Error>(&Test { hello: 2 }).unwrap();
+    /// // Scramble the bytes, as they might be stored on disk.
+    /// bytes.iter_mut().for_each(|b| *b = !*b);
+    ///
+    /// let unscramble =
+    ///     |bytes: &mut [u8]| bytes.iter_mut().for_each(|b| *b = !*b);
+    /// let owned_archive =
+    ///     OwnedArchive::<Test, _>::new_with_inplace_transform::<Error>(
+    ///         bytes, unscramble,
+    ///     )
+    ///     .unwrap();
+    /// assert_eq!(owned_archive.hello, 2);
+    /// ```
+    pub fn new_with_inplace_transform<E>(
+        mut container: C,
+        transform: impl FnOnce(&mut [u8]),
+    ) -> Result<Self, E>
+    where
+        T: Archive,
+        T::Archived: ValidateArchive<E>,
+        E: rkyv::rancor::Source,
+        C: StableBytesMut,
+    {
+        transform(container.bytes_mut());
+        Self::new::<E>(container)
+    }
+
+    /// Creates an `OwnedArchive` without validating the container.
+    ///
+    /// This is for bytes that were already validated elsewhere, for example
+    /// by an upstream service.
+    ///
+    /// # Safety
+    /// The bytes of `container` must be a valid archive of `T`, exactly as
+    /// [`new`](Self::new) would check, and `container` must uphold the
+    /// `StableBytes` contract.
+    pub unsafe fn new_unchecked(container: C) -> Self {
+        Self {
+            container,
+            _type: PhantomData,
+        }
+    }
+
+    /// Reassembles an `OwnedArchive` from the parts returned by
+    /// [`into_parts`](Self::into_parts), without validating it again.
+    ///
+    /// # Safety
+    /// The bytes of `container` must be a valid archive of `T` whose root is
+    /// at `root_pos`, exactly as [`new`](Self::new) would check, and
+    /// `container` must uphold the `StableBytes` contract. Archives are
+    /// always rooted at the end of their bytes, so `root_pos` must be the
+    /// length of the bytes minus the size of `T::Archived`; this is checked
+    /// in debug builds.
+    pub unsafe fn from_parts(container: C, root_pos: usize) -> Self
+    where
+        T: Archive,
+        C: StableBytes,
+    {
+        debug_assert_eq!(
+            Some(root_pos),
+            container
+                .bytes()
+                .len()
+                .checked_sub(core::mem::size_of::<T::Archived>()),
+            "root position does not match the container",
+        );
+        // # Safety
+        // The caller guarantees that the container holds a valid archive.
+        unsafe { Self::new_unchecked(container) }
+    }
+
+    /// Splits the archive into its container and the position of its root
+    /// within the container's bytes.
+    ///
+    /// [`from_parts`](Self::from_parts) puts them back together.
+    ///
+    /// # Example
+    /// ```
+    /// use rkyv::rancor::Error;
+    /// use rkyv_util::owned::OwnedArchive;
+    ///
+    /// #[derive(rkyv::Archive, rkyv::Serialize)]
+    /// pub struct Test {
+    ///     hello: u8,
+    /// }
+    ///
+    /// let bytes = rkyv::to_bytes::<Error>(&Test { hello: 2 }).unwrap();
+    /// let owned_archive =
+    ///     OwnedArchive::<Test, _>::new::<Error>(bytes).unwrap();
+    ///
+    /// let (bytes, root_pos) = owned_archive.into_parts();
+    /// // SAFETY: The parts came straight from `into_parts`.
+    /// let owned_archive =
+    ///     unsafe { OwnedArchive::<Test, _>::from_parts(bytes, root_pos) };
+    /// assert_eq!(owned_archive.hello, 2);
+    /// ```
+    pub fn into_parts(self) -> (C, usize)
+    where
+        T: Archive,
+        C: StableBytes,
+    {
+        let root_pos =
+            self.container.bytes().len() - core::mem::size_of::<T::Archived>();
+        (self.container, root_pos)
+    }
+
+    /// Creates an `OwnedArchive` without validating the container, runs `f`
+    /// on it and returns both.
+    ///
+    /// This suits pipelines where validation happened upstream and the
+    /// archive only needs a quick look before being passed along.
+    ///
+    /// # Safety
+    /// This has the same requirements as
+    /// [`new_unchecked`](Self::new_unchecked).
+    ///
+    /// # Example
+    /// ```
+    /// use rkyv::rancor::Error;
+    /// use rkyv_util::owned::OwnedArchive;
+    ///
+    /// #[derive(rkyv::Archive, rkyv::Serialize)]
+    /// pub struct Test {
+    ///     hello: u8,
+    /// }
+    ///
+    /// let bytes = rkyv::to_bytes::<Error>(&Test { hello: 2 }).unwrap();
+    ///
+    /// // SAFETY: These bytes were just serialized from a `Test`.
+    /// let (owned_archive, hello) = unsafe {
+    ///     OwnedArchive::<Test, _>::assume_valid_then(bytes, |archive| {
+    ///         archive.hello
+    ///     })
+    /// };
+    /// assert_eq!(hello, 2);
+    /// assert_eq!(owned_archive.hello, 2);
+    /// ```
+    pub unsafe fn assume_valid_then<R>(
+        container: C,
+        f: impl FnOnce(&Self) -> R,
+    ) -> (Self, R) {
+        // # Safety
+        // The caller upholds the requirements of `new_unchecked`.
+        let archive = unsafe { Self::new_unchecked(container) };
+        let result = f(&archive);
+        (archive, result)
+    }
+
+    /// Relabels the archive as an archive of `U`, without touching or
+    /// revalidating its bytes.
+    ///
+    /// This is useful during refactors, when a type has been renamed or
+    /// wrapped but its archived layout has stayed the same.
+    ///
+    /// # Safety
+    /// The bytes must be a valid archive of `U`, exactly as
+    /// [`new`](Self::new) would check. This holds when `U::Archived` is
+    /// layout-compatible with `T::Archived` and accepts every value of it,
+    /// for example when one is a `#[repr(transparent)]` wrapper of the other.
+    pub unsafe fn rewrap<U>(self) -> OwnedArchive<U, C> {
+        // # Safety
+        // The caller guarantees that the bytes are a valid archive of `U`.
+        unsafe { OwnedArchive::new_unchecked(self.container) }
+    }
+
+    /// Gets the pinned object as mutable, if the container is mutable.
+    ///
+    /// This is the runtime counterpart to [`get_mut`](Self::get_mut) for
+    /// generic code: it returns `None` instead of failing to compile when the
+    /// container is read-only.
+    ///
+    /// # Example
+    /// ```
+    /// use rkyv::rancor::Error;
+    /// use rkyv_util::owned::OwnedArchive;
+    ///
+    /// #[derive(rkyv::Archive, rkyv::Serialize)]
+    /// pub struct Test {
+    ///     hello: u8,
+    /// }
+    ///
+    /// let bytes = rkyv::to_bytes::<Error>(&Test { hello: 2 }).unwrap();
+    ///
+    /// let mut owned_archive =
+    ///     OwnedArchive::<Test, &[u8]>::new::<Error>(bytes.as_slice())
+    ///         .unwrap();
+    /// assert!(owned_archive.try_get_mut().is_none());
+    /// ```
+    pub fn try_get_mut(&mut self) -> Option<Seal<'_, T::Archived>>
+    where
+        T: Archive,
+        T::Archived: Portable,
+        C: ByteBacking,
+    {
+        let bytes = self.container.try_bytes_mut()?;
+
+        // # Safety
+        // `ByteBacking` only hands out mutable bytes for containers that
+        // implement `StableBytesMut`, so this is the same access as
+        // `get_mut`.
+        Some(unsafe { access_root_mut::<T::Archived>(bytes) })
+    }
+
+    /// Returns whether the archive can be mutated through
+    /// [`try_get_mut`](Self::try_get_mut).
+    pub fn is_mutable(&self) -> bool
+    where
+        C: ByteBacking,
+    {
+        C::MUTABLE
+    }
+
+    /// Gets a shared handle to the archive's bytes.
+    ///
+    /// The bytes are copied into a new `Arc` once, unless the archive is
+    /// already backed by an `Arc<[u8]>`, in which case that `Arc` is cloned.
+    /// The typed archive is left untouched, so the bytes can be handed to
+    /// another subsystem while it keeps being used.
+    ///
+    /// # Example
+    /// ```
+    /// use std::sync::Arc;
+    ///
+    /// use rkyv::rancor::Error;
+    /// use rkyv_util::owned::OwnedArchive;
+    ///
+    /// #[derive(rkyv::Archive, rkyv::Serialize)]
+    /// pub struct Test {
+    ///     hello: u8,
+    /// }
+    ///
+    /// let bytes = rkyv::to_bytes::<Error>(&Test { hello: 2 }).unwrap();
+    /// let shared: Arc<[u8]> = Arc::from(bytes.as_slice());
+    ///
+    /// let owned_archive =
+    ///     OwnedArchive::<Test, _>::new::<Error>(shared.clone()).unwrap();
+    /// assert!(Arc::ptr_eq(&owned_archive.bytes_arc(), &shared));
+    /// ```
+    pub fn bytes_arc(&self) -> Arc<[u8]>
+    where
+        C: ByteBacking,
+    {
+        self.container.to_arc()
+    }
+
+    /// Unwraps the underlying container.
+    pub(crate) fn into_container(self) -> C {
+        self.container
+    }
+
+    /// Gets a reference to the underlying container.
+    ///
+    /// This is for inspecting the container while keeping the archive, such
+    /// as the capacity of a `Vec<u8>` or the mapping behind a `SharedMmap`.
+    ///
+    /// # Example
+    /// ```
+    /// use rkyv::rancor::Error;
+    /// use rkyv_util::owned::OwnedArchive;
+    ///
+    /// #[derive(rkyv::Archive, rkyv::Serialize)]
+    /// pub struct Test {
+    ///     hello: u8,
+    /// }
+    ///
+    /// let bytes = rkyv::to_bytes::<Error>(&Test { hello: 2 }).unwrap();
+    /// let owned_archive =
+    ///     OwnedArchive::<Test, _>::new::<Error>(bytes.to_vec()).unwrap();
+    /// assert_eq!(owned_archive.container().len(), bytes.len());
+    /// ```
+    pub fn container(&self) -> &C {
+        &self.container
+    }
+
+    /// Creates a new `OwnedArchive`, boxing any validation error.
+    ///
+    /// This saves converting rkyv's error at every call site when the
+    /// surrounding code already works with boxed errors. Other error types
+    /// can be reached by `map_err` on the result of [`new`](Self::new) with
+    /// a concrete error type such as `rkyv::rancor::Error`.
+    ///
+    /// # Example
+    /// ```
+    /// use std::error::Error;
+    ///
+    /// use rkyv::{rancor, util::AlignedVec};
+    /// use rkyv_util::owned::OwnedArchive;
+    ///
+    /// #[derive(rkyv::Archive, rkyv::Serialize)]
+    /// pub struct Test {
+    ///     hello: u8,
+    /// }
+    ///
+    /// type BoxedError = Box<dyn Error + Send + Sync>;
+    ///
+    /// fn load(
+    ///     bytes: AlignedVec,
+    /// ) -> Result<OwnedArchive<Test, AlignedVec>, BoxedError> {
+    ///     let archive = OwnedArchive::new_boxed(bytes)?;
+    ///     Ok(archive)
+    /// }
+    ///
+    /// let bytes =
+    ///     rkyv::to_bytes::<rancor::Error>(&Test { hello: 2 }).unwrap();
+    /// assert_eq!(load(bytes).unwrap().hello, 2);
+    /// ```
+    pub fn new_boxed(container: C) -> Result<Self, Box<dyn Error + Send + Sync>>
+    where
+        T: Archive,
+        T::Archived: ValidateArchive<BoxedError>,
+        C: StableBytes,
+    {
+        Ok(Self::new::<BoxedError>(container)?)
+    }
+
+    /// Gets the pinned object as mutable.
+    ///
+    /// # Example
+    /// ```
+    /// use rkyv::{rancor::Error, util::AlignedVec, munge::munge};
+    /// use rkyv_util::owned::OwnedArchive;
+    ///
+    /// #[derive(rkyv::Archive, rkyv::Serialize)]
+    /// pub struct Test {
+    ///     hello: u8,
+    /// }
+    ///
+    /// let bytes = rkyv::to_bytes::<Error>(&Test { hello: 2 }).unwrap();
+    ///
+    /// let owned_archive =
+    ///     &mut OwnedArchive::<Test, _>::new::<Error>(bytes).unwrap();
+    /// assert_eq!(owned_archive.hello, 2);
+    ///
+    /// munge!(let ArchivedTest { mut hello, ..} = owned_archive.get_mut());
+    /// *hello = 3;
+    /// // `hello` should be 3 back in the owned archive.
+    /// assert_eq!(*hello, 3);
+    /// assert_eq!(owned_archive.hello, 3);
+    /// ```
+    pub fn get_mut(&mut self) -> Seal<'_, T::Archived>
+    where
+        T: Archive,
+        T::Archived: Portable,
+        C: StableBytesMut,
+    {
+        // # Safety
+        // Here we can safely access the underlying archive. This is
+        // because `StableBytesMut` enforces the safety contract that the
+        // underlying bytes remain stable, and thus the container that
+        // we took ownership of when creating the `OwnedArchive` has
+        // already been created.
+        unsafe { access_root_mut::<T::Archived>(self.container.bytes_mut()) }
+    }
+
+    /// Deserializes the archive into an owned `T`.
+    ///
+    /// # Example
+    /// ```
+    /// use rkyv::rancor::Error;
+    /// use rkyv_util::owned::OwnedArchive;
+    ///
+    /// #[derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)]
+    /// pub struct Test {
+    ///     hello: u8,
+    /// }
+    ///
+    /// let bytes = rkyv::to_bytes::<Error>(&Test { hello: 2 }).unwrap();
+    ///
+    /// let owned_archive =
+    ///     OwnedArchive::<Test, _>::new::<Error>(bytes).unwrap();
+    /// let test = owned_archive.deserialize::<Error>().unwrap();
+    /// assert_eq!(test.hello, 2);
+    /// ```
+    pub fn deserialize<E>(&self) -> Result<T, E>
+    where
+        T: Archive,
+        T::Archived: Deserialize<T, HighDeserializer<E>>,
+        E: rkyv::rancor::Source,
+        C: StableBytes,
+    {
+        rkyv::deserialize::<T, E>(self.deref())
+    }
+
+    /// Deserializes the archive into an owned `T`, using a caller-held pool.
+    ///
+    /// The pool is what rkyv's high-level deserializer uses to deserialize
+    /// each shared pointer, like an `Rc` or `Arc`, only once. Reusing one
+    /// keeps it from being reallocated for every archive in a stream, and
+    /// shares those pointers between the values deserialized with it.
+    ///
+    /// The pool remembers shared pointers by their address in the archive.
+    /// Only reuse it while every archive deserialized with it is still alive,
+    /// since a new archive at a freed address would be handed the pointers of
+    /// the old one.
+    ///
+    /// # Example
+    /// ```
+    /// use rkyv::{de::Pool, rancor::Error};
+    /// use rkyv_util::owned::OwnedArchive;
+    ///
+    /// #[derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)]
+    /// pub struct Test {
+    ///     hello: u8,
+    /// }
+    ///
+    /// let bytes = rkyv::to_bytes::<Error>(&Test { hello: 2 }).unwrap();
+    /// let owned_archive =
+    ///     OwnedArchive::<Test, _>::new::<Error>(bytes).unwrap();
+    ///
+    /// let mut pool = Pool::new();
+    /// let test = owned_archive.deserialize_with_pool::<Error>(&mut pool);
+    /// assert_eq!(test.unwrap().hello, 2);
+    /// ```
+    pub fn deserialize_with_pool<E>(&self, pool: &mut Pool) -> Result<T, E>
+    where
+        T: Archive,
+        T::Archived: Deserialize<T, HighDeserializer<E>>,
+        E: rkyv::rancor::Source,
+        C: StableBytes,
+    {
+        rkyv::api::deserialize_using::<T, Pool, E>(self.deref(), pool)
+    }
+
+    /// Deserializes the archive into a boxed `Dyn`, such as a trait object
+    /// that `T` implements.
+    ///
+    /// `coerce` turns the boxed value into a `Box<Dyn>`. As with
+    /// [`BoxedArchived`](crate::boxed::BoxedArchived), `|value| value` is
+    /// enough as long as `Dyn` is named.
+    ///
+    /// # Example
+    /// ```
+    /// use rkyv::rancor::Error;
+    /// use rkyv_util::owned::OwnedArchive;
+    ///
+    /// #[derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)]
+    /// pub struct Test {
+    ///     hello: u8,
+    /// }
+    ///
+    /// trait Describe {
+    ///     fn describe(&self) -> String;
+    /// }
+    ///
+    /// impl Describe for Test {
+    ///     fn describe(&self) -> String {
+    ///         format!("hello is {}", self.hello)
+    ///     }
+    /// }
+    ///
+    /// let bytes = rkyv::to_bytes::<Error>(&Test { hello: 2 }).unwrap();
+    /// let owned_archive =
+    ///     OwnedArchive::<Test, _>::new::<Error>(bytes).unwrap();
+    ///
+    /// let boxed = owned_archive
+    ///     .deserialize_boxed::<dyn Describe, Error>(|value| value)
+    ///     .unwrap();
+    /// assert_eq!(boxed.describe(), "hello is 2");
+    /// ```
+    pub fn deserialize_boxed<Dyn, E>(
+        &self,
+        coerce: impl FnOnce(Box<T>) -> Box<Dyn>,
+    ) -> Result<Box<Dyn>, E>
+    where
+        Dyn: ?Sized,
+        T: Archive,
+        T::Archived: Deserialize<T, HighDeserializer<E>>,
+        E: rkyv::rancor::Source,
+        C: StableBytes,
+    {
+        Ok(coerce(Box::new(self.deserialize::<E>()?)))
+    }
+
+    /// Deserializes a single field of the archive.
+    ///
+    /// `field` picks the archived field out of the root, and only that field
+    /// is deserialized, and it may reach through nested structs. The field's
+    /// type `U` must be deserializable from its archived form with rkyv's
+    /// high-level deserializer, which is the case for any type deriving
+    /// `Deserialize`.
+    ///
+    /// # Example
+    /// ```
+    /// use rkyv::rancor::Error;
+    /// use rkyv_util::owned::OwnedArchive;
+    ///
+    /// #[derive(rkyv::Archive, rkyv::Serialize)]
+    /// pub struct Test {
+    ///     hello: u8,
+    ///     names: Vec<String>,
+    /// }
+    ///
+    /// let test = Test {
+    ///     hello: 2,
+    ///     names: vec!["world".to_string()],
+    /// };
+    /// let bytes = rkyv::to_bytes::<Error>(&test).unwrap();
+    ///
+    /// let owned_archive =
+    ///     OwnedArchive::<Test, _>::new::<Error>(bytes).unwrap();
+    /// let names = owned_archive
+    ///     .deserialize_field::<Vec<String>, Error>(|test| &test.names)
+    ///     .unwrap();
+    /// assert_eq!(names, ["world"]);
+    /// ```
+    pub fn deserialize_field<U, E>(
+        &self,
+        field: impl FnOnce(&T::Archived) -> &U::Archived,
+    ) -> Result<U, E>
+    where
+        T: Archive,
+        U: Archive,
+        U::Archived: Deserialize<U, HighDeserializer<E>>,
+        E: rkyv::rancor::Source,
+        C: StableBytes,
+    {
+        rkyv::deserialize::<U, E>(field(self.deref()))
+    }
+
+    /// Projects into an optional part of the archive, falling back to
+    /// `default` when it is absent.
+    ///
+    /// `f` usually ends in `ArchivedOption::as_ref`, which turns an archived
+    /// `Option` field into an `Option` of a reference.
+    ///
+    /// # Example
+    /// ```
+    /// use rkyv::{rancor::Error, Archived};
+    /// use rkyv_util::owned::OwnedArchive;
+    ///
+    /// #[derive(rkyv::Archive, rkyv::Serialize)]
+    /// pub struct Test {
+    ///     retries: Option<u32>,
+    /// }
+    ///
+    /// let bytes = rkyv::to_bytes::<Error>(&Test { retries: None }).unwrap();
+    /// let owned_archive =
+    ///     OwnedArchive::<Test, _>::new::<Error>(bytes).unwrap();
+    ///
+    /// let default = Archived::<u32>::from_native(3);
+    /// let retries = owned_archive
+    ///     .map_or::<u32>(&default, |test| test.retries.as_ref());
+    /// assert_eq!(*retries, 3);
+    /// ```
+    pub fn map_or<'a, U>(
+        &'a self,
+        default: &'a U::Archived,
+        f: impl FnOnce(&'a T::Archived) -> Option<&'a U::Archived>,
+    ) -> &'a U::Archived
+    where
+        T: Archive,
+        U: Archive,
+        C: StableBytes,
+    {
+        f(self.deref()).unwrap_or(default)
+    }
+
+    /// Creates a new `OwnedArchive` and deserializes it in one call.
+    ///
+    /// This is the common "load and use" case, where the archive is kept
+    /// around but an owned copy of the value is needed right away.
+    ///
+    /// # Example
+    /// ```
+    /// use rkyv::rancor::Error;
+    /// use rkyv_util::owned::OwnedArchive;
+    ///
+    /// #[derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)]
+    /// pub struct Test {
+    ///     hello: u8,
+    /// }
+    ///
+    /// let bytes = rkyv::to_bytes::<Error>(&Test { hello: 2 }).unwrap();
+    ///
+    /// let (owned_archive, test) =
+    ///     OwnedArchive::<Test, _>::new_and_deserialize::<Error>(bytes)
+    ///         .unwrap();
+    /// assert_eq!(owned_archive.hello, test.hello);
+    /// ```
+    pub fn new_and_deserialize<E>(container: C) -> Result<(Self, T), E>
+    where
+        T: Archive,
+        T::Archived: ValidateArchive<E> + Deserialize<T, HighDeserializer<E>>,
+        E: rkyv::rancor::Source,
+        C: StableBytes,
+    {
+        let archive = Self::new::<E>(container)?;
+        let value = archive.deserialize::<E>()?;
+        Ok((archive, value))
+    }
+}
+
+impl<T, C: StableBytes> OwnedArchive<T, C> {
+    /// Gets the bytes of the underlying container.
+    pub fn as_bytes(&self) -> &[u8] {
+        self.container.bytes()
+    }
+
+    /// Validates the archive again and gets the archived root.
+    ///
+    /// `Deref` trusts that the bytes are still valid, which they might not be
+    /// after edits made through [`Seal::unseal_unchecked`]. Prefer this over
+    /// `Deref` for reads that follow such edits: corruption is then reported
+    /// as an error instead of being read as undefined behavior.
+    ///
+    /// # Example
+    /// ```
+    /// use rkyv::rancor::Error;
+    /// use rkyv_util::owned::OwnedArchive;
+    ///
+    /// #[derive(rkyv::Archive, rkyv::Serialize)]
+    /// pub struct Test {
+    ///     hello: u8,
+    /// }
+    ///
+    /// let bytes = rkyv::to_bytes::<Error>(&Test { hello: 2 }).unwrap();
+    /// let owned_archive =
+    ///     OwnedArchive::<Test, _>::new::<Error>(bytes).unwrap();
+    /// assert_eq!(owned_archive.try_deref::<Error>().unwrap().hello, 2);
+    /// ```
+    pub fn try_deref<E>(&self) -> Result<&T::Archived, E>
+    where
+        T: Archive,
+        T::Archived: ValidateArchive<E>,
+        E: rkyv::rancor::Source,
+    {
+        let bytes = self.as_bytes();
+        validate_root::<T::Archived, E>(bytes, None)?;
+        // # Safety
+        // The bytes were just validated.
+        Ok(unsafe { access_root::<T::Archived>(bytes) })
+    }
+
+    /// Clones the archive and validates the clone.
+    ///
+    /// Like [`try_deref`](Self::try_deref), this catches corruption from
+    /// edits made through [`Seal::unseal_unchecked`], so the result is a
+    /// snapshot that is known to be valid.
+    ///
+    /// # Example
+    /// ```
+    /// use rkyv::rancor::Error;
+    /// use rkyv_util::owned::OwnedArchive;
+    ///
+    /// #[derive(rkyv::Archive, rkyv::Serialize)]
+    /// pub struct Test {
+    ///     hello: u8,
+    /// }
+    ///
+    /// let bytes = rkyv::to_bytes::<Error>(&Test { hello: 2 }).unwrap();
+    /// let owned_archive =
+    ///     OwnedArchive::<Test, _>::new::<Error>(bytes).unwrap();
+    /// let snapshot = owned_archive.checked_clone::<Error>().unwrap();
+    /// assert_eq!(snapshot.hello, 2);
+    /// ```
+    pub fn checked_clone<E>(&self) -> Result<Self, E>
+    where
+        T: Archive,
+        T::Archived: ValidateArchive<E>,
+        E: rkyv::rancor::Source,
+        C: Clone,
+    {
+        Self::new::<E>(self.container.clone())
+    }
+
+    /// Borrows the archive as a cheap, read-only [`BorrowedArchive`].
+    ///
+    /// The bytes have already been validated, so this does not validate them
+    /// again.
+    ///
+    /// # Example
+    /// ```
+    /// use rkyv::rancor::Error;
+    /// use rkyv_util::owned::{BorrowedArchive, OwnedArchive};
+    ///
+    /// #[derive(rkyv::Archive, rkyv::Serialize)]
+    /// pub struct Test {
+    ///     hello: u8,
+    /// }
+    ///
+    /// fn read(archive: BorrowedArchive<'_, Test>) -> u8 {
+    ///     archive.hello
+    /// }
+    ///
+    /// let bytes = rkyv::to_bytes::<Error>(&Test { hello: 2 }).unwrap();
+    /// let owned_archive =
+    ///     OwnedArchive::<Test, _>::new::<Error>(bytes).unwrap();
+    /// assert_eq!(read(owned_archive.reborrow()), 2);
+    /// ```
+    pub fn reborrow(&self) -> BorrowedArchive<'_, T> {
+        // # Safety
+        // These are the same bytes that were validated for this archive, and
+        // shared bytes can't change for as long as they are borrowed.
+        unsafe { OwnedArchive::new_unchecked(self.as_bytes()) }
+    }
+
+    /// Returns whether the archive's bytes are exactly `other`.
+    ///
+    /// This compares raw bytes, which makes it a cheap way to deduplicate
+    /// archives. Archives that are structurally equal may still have
+    /// different bytes, for instance if they were serialized in a different
+    /// order.
+    ///
+    /// # Example
+    /// ```
+    /// use rkyv::rancor::Error;
+    /// use rkyv_util::owned::OwnedArchive;
+    ///
+    /// #[derive(rkyv::Archive, rkyv::Serialize)]
+    /// pub struct Test {
+    ///     hello: u8,
+    /// }
+    ///
+    /// let bytes = rkyv::to_bytes::<Error>(&Test { hello: 2 }).unwrap();
+    /// let owned_archive =
+    ///     OwnedArchive::<Test, _>::new::<Error>(bytes.clone()).unwrap();
+    /// assert!(owned_archive.equals_bytes(&bytes));
+    /// ```
+    pub fn equals_bytes(&self, other: &[u8]) -> bool {
+        self.as_bytes() == other
+    }
+
+    /// Gets a pointer to the start of the archive's bytes, for passing the
+    /// archive across an FFI boundary.
+    ///
+    /// The pointer is valid for reads of [`len`](Self::len) bytes for as long
+    /// as this archive is alive and not mutated. The bytes do not move in the
+    /// meantime, as required by `StableBytes`.
+    ///
+    /// # Example
+    /// ```
+    /// use rkyv::rancor::Error;
+    /// use rkyv_util::owned::OwnedArchive;
+    ///
+    /// #[derive(rkyv::Archive, rkyv::Serialize)]
+    /// pub struct Test {
+    ///     hello: u8,
+    /// }
+    ///
+    /// let bytes = rkyv::to_bytes::<Error>(&Test { hello: 2 }).unwrap();
+    /// let owned_archive =
+    ///     OwnedArchive::<Test, _>::new::<Error>(bytes).unwrap();
+    ///
+    /// // SAFETY: The archive is alive and unchanged while the slice is used.
+    /// let bytes = unsafe {
+    ///     core::slice::from_raw_parts(
+    ///         owned_archive.as_ptr(),
+    ///         owned_archive.len(),
+    ///     )
+    /// };
+    /// assert_eq!(bytes, owned_archive.as_bytes());
+    /// ```
+    pub fn as_ptr(&self) -> *const u8 {
+        self.as_bytes().as_ptr()
+    }
+
+    /// Gets a pointer to the archived root object.
+    ///
+    /// This carries the same guarantees as [`as_ptr`](Self::as_ptr): it is
+    /// valid for as long as this archive is alive and not mutated.
+    pub fn archived_ptr(&self) -> *const T::Archived
+    where
+        T: Archive,
+    {
+        self.deref()
+    }
+
+    /// Gets the length of the archive's bytes.
+    pub fn len(&self) -> usize {
+        self.as_bytes().len()
+    }
+
+    /// Returns whether the archive's bytes are empty.
+    ///
+    /// This is only possible for archived types with a size of zero.
+    pub fn is_empty(&self) -> bool {
+        self.as_bytes().is_empty()
+    }
+
+    /// Serializes the raw archive bytes with a serde serializer.
+    ///
+    /// This embeds the archive as an opaque byte blob in a larger serde
+    /// document, rather than serializing the archived value. It can be used
+    /// with `#[serde(serialize_with = "...")]` on a field holding an
+    /// `OwnedArchive`. Reading the bytes back and passing them to
+    /// [`new`](Self::new) recovers the archive.
+    ///
+    /// # Example
+    /// ```
+    /// use rkyv::rancor::Error;
+    /// use rkyv_util::owned::OwnedArchive;
+    ///
+    /// #[derive(rkyv::Archive, rkyv::Serialize)]
+    /// pub struct Test {
+    ///     hello: u8,
+    /// }
+    ///
+    /// let bytes = rkyv::to_bytes::<Error>(&Test { hello: 2 }).unwrap();
+    /// let owned_archive =
+    ///     OwnedArchive::<Test, _>::new::<Error>(bytes).unwrap();
+    ///
+    /// let mut encoded = Vec::new();
+    /// owned_archive
+    ///     .serialize_bytes_with(&mut bincode::Serializer::new(
+    ///         &mut encoded,
+    ///         bincode::DefaultOptions::new(),
+    ///     ))
+    ///     .unwrap();
+    /// ```
+    #[cfg(feature = "serde")]
+    pub fn serialize_bytes_with<S: serde::Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(self.as_bytes())
+    }
+
+    /// Computes a checksum of the raw archive bytes.
+    ///
+    /// This is a fast, non-cryptographic hash (XXH3) for detecting bit-rot
+    /// by comparing against a known-good value, for example one recorded in
+    /// a manifest. It says nothing about whether the bytes are a valid
+    /// archive; that is what [`new`](Self::new) checks.
+    ///
+    /// # Example
+    /// ```
+    /// use rkyv::rancor::Error;
+    /// use rkyv_util::owned::OwnedArchive;
+    ///
+    /// #[derive(rkyv::Archive, rkyv::Serialize)]
+    /// pub struct Test {
+    ///     hello: u8,
+    /// }
+    ///
+    /// let bytes = rkyv::to_bytes::<Error>(&Test { hello: 2 }).unwrap();
+    /// let owned_archive =
+    ///     OwnedArchive::<Test, _>::new::<Error>(bytes).unwrap();
+    /// let expected = owned_archive.checksum();
+    ///
+    /// assert_eq!(owned_archive.clone().checksum(), expected);
+    /// ```
+    #[cfg(feature = "checksum")]
+    pub fn checksum(&self) -> u64 {
+        xxhash_rust::xxh3::xxh3_64(self.as_bytes())
+    }
+
+    /// Creates a new `OwnedArchive` after checking its bytes against a
+    /// checksum computed by [`par_chunked_checksum`].
+    ///
+    /// The checksum is computed in parallel before anything else, so a
+    /// corrupted buffer is rejected without being validated. Validation
+    /// itself still runs sequentially like in [`new`](Self::new).
+    ///
+    /// # Panics
+    /// Panics if `chunk_size` is 0.
+    ///
+    /// # Example
+    /// ```
+    /// use rkyv::rancor::Error;
+    /// use rkyv_util::owned::{par_chunked_checksum, OwnedArchive};
+    ///
+    /// #[derive(rkyv::Archive, rkyv::Serialize)]
+    /// pub struct Test {
+    ///     hello: u8,
+    /// }
+    ///
+    /// let bytes = rkyv::to_bytes::<Error>(&Test { hello: 2 }).unwrap();
+    /// let expected = par_chunked_checksum(&bytes, 64);
+    ///
+    /// let owned_archive =
+    ///     OwnedArchive::<Test, _>::new_verified::<Error>(bytes, 64, expected)
+    ///         .unwrap();
+    /// assert_eq!(owned_archive.hello, 2);
+    /// ```
+    #[cfg(feature = "rayon")]
+    pub fn new_verified<E>(
+        container: C,
+        chunk_size: usize,
+        expected: u64,
+    ) -> Result<Self, E>
+    where
+        T: Archive,
+        T::Archived: ValidateArchive<E>,
+        E: rkyv::rancor::Source,
+        C: StableBytes,
+    {
+        let actual = par_chunked_checksum(container.bytes(), chunk_size);
+        if actual != expected {
+            return Err(E::new(ChecksumMismatch { expected, actual }));
+        }
+        Self::new::<E>(container)
+    }
+
+    /// Copies the archive into a new container of type `C2`, keeping the
+    /// original.
+    ///
+    /// The copy is validated again, since the new buffer may not share the
+    /// alignment of the original one.
+    ///
+    /// # Example
+    /// ```
+    /// use std::sync::Arc;
+    ///
+    /// use rkyv::{rancor::Error, util::AlignedVec};
+    /// use rkyv_util::owned::OwnedArchive;
+    ///
+    /// #[derive(rkyv::Archive, rkyv::Serialize)]
+    /// pub struct Test {
+    ///     hello: u8,
+    /// }
+    ///
+    /// let bytes = rkyv::to_bytes::<Error>(&Test { hello: 2 }).unwrap();
+    /// let owned_archive =
+    ///     OwnedArchive::<Test, _>::new::<Error>(bytes).unwrap();
+    ///
+    /// let shared = owned_archive
+    ///     .clone_into_container::<Arc<[u8]>, Error>()
+    ///     .unwrap();
+    /// assert_eq!(shared.hello, 2);
+    /// assert_eq!(owned_archive.hello, 2);
+    /// ```
+    pub fn clone_into_container<C2, E>(&self) -> Result<OwnedArchive<T, C2>, E>
+    where
+        T: Archive,
+        T::Archived: ValidateArchive<E>,
+        E: rkyv::rancor::Source,
+        C2: FromBytes,
+    {
+        OwnedArchive::new::<E>(C2::from_bytes(self.as_bytes()))
+    }
+
+    /// Turns a mutable archive into an immutable, shareable one by moving
+    /// its container into an `Arc`.
+    ///
+    /// This is for taking a snapshot once edits are finished, which can then
+    /// be cloned cheaply and sent to and read from other threads. The bytes
+    /// are neither copied nor validated again: they stay where they are, and
+    /// the `Arc` only hands out shared references to the container, so they
+    /// can no longer change. An `Arc<[u8]>` would need a copy that may not
+    /// keep the alignment of the original buffer; use
+    /// [`bytes_arc`](Self::bytes_arc) for the raw bytes instead.
+    ///
+    /// # Example
+    /// ```
+    /// use rkyv::{munge::munge, rancor::Error};
+    /// use rkyv_util::owned::OwnedArchive;
+    ///
+    /// #[derive(rkyv::Archive, rkyv::Serialize)]
+    /// pub struct Test {
+    ///     hello: u8,
+    /// }
+    ///
+    /// let bytes = rkyv::to_bytes::<Error>(&Test { hello: 2 })?;
+    /// let mut owned_archive = OwnedArchive::<Test, _>::new::<Error>(bytes)?;
+    /// munge!(let ArchivedTest { mut hello } = owned_archive.get_mut());
+    /// *hello = 3;
+    ///
+    /// let frozen = owned_archive.freeze();
+    /// let shared = frozen.clone();
+    /// assert_eq!(shared.hello, 3);
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn freeze(self) -> OwnedArchive<T, Arc<C>>
+    where
+        C: StableBytesMut,
+    {
+        // # Safety
+        // Moving the container into an `Arc` leaves its bytes where they
+        // were validated, by the `StableBytes` contract.
+        unsafe { OwnedArchive::new_unchecked(Arc::new(self.container)) }
+    }
+
+    /// Copies the archive into a tightly sized `AlignedVec` aligned to `A`
+    /// bytes.
+    ///
+    /// This frees the spare capacity of a long-lived archive, such as one
+    /// read into a buffer sized for the largest possible file. Archives are
+    /// rooted at the very end of their bytes, so everything in the buffer is
+    /// kept; only the allocation shrinks, to a capacity of exactly the
+    /// archive's length. The copy is validated again, like
+    /// [`clone_into_container`](Self::clone_into_container), so `A` must be
+    /// at least the alignment the archive was serialized with, as for
+    /// [`new_aligned_to`](OwnedArchive::new_aligned_to).
+    ///
+    /// # Example
+    /// ```
+    /// use rkyv::{rancor::Error, util::AlignedVec};
+    /// use rkyv_util::owned::OwnedArchive;
+    ///
+    /// #[derive(rkyv::Archive, rkyv::Serialize)]
+    /// pub struct Test {
+    ///     hello: u8,
+    /// }
+    ///
+    /// let mut bytes = AlignedVec::<16>::with_capacity(4096);
+    /// bytes.extend_from_slice(&rkyv::to_bytes::<Error>(&Test { hello: 2 })?);
+    /// let owned_archive = OwnedArchive::<Test, _>::new::<Error>(bytes)?;
+    ///
+    /// let compacted = owned_archive.compact::<Error, 16>()?;
+    /// assert_eq!(compacted.hello, 2);
+    /// assert_eq!(compacted.container().capacity(), compacted.len());
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn compact<E, const A: usize>(
+        &self,
+    ) -> Result<OwnedArchive<T, AlignedVec<A>>, E>
+    where
+        T: Archive,
+        T::Archived: ValidateArchive<E>,
+        E: rkyv::rancor::Source,
+    {
+        let bytes = self.as_bytes();
+        let mut compacted = AlignedVec::<A>::with_capacity(bytes.len());
+        compacted.extend_from_slice(bytes);
+        OwnedArchive::new::<E>(compacted)
+    }
+
+    /// Checks whether the bytes of this archive are also a valid archive of
+    /// `U`, without changing its type.
+    ///
+    /// This is a way to gain confidence before calling
+    /// [`rewrap`](Self::rewrap) during a schema migration. Success only means
+    /// that these particular bytes are valid for `U`; it does not prove that
+    /// every archive of `T` is.
+    ///
+    /// # Example
+    /// ```
+    /// use rkyv::rancor::Error;
+    /// use rkyv_util::owned::OwnedArchive;
+    ///
+    /// #[derive(rkyv::Archive, rkyv::Serialize)]
+    /// pub struct Old {
+    ///     hello: u32,
+    /// }
+    ///
+    /// #[derive(rkyv::Archive, rkyv::Serialize)]
+    /// pub struct New {
+    ///     renamed: u32,
+    /// }
+    ///
+    /// let bytes = rkyv::to_bytes::<Error>(&Old { hello: 2 }).unwrap();
+    /// let owned_archive =
+    ///     OwnedArchive::<Old, _>::new::<Error>(bytes).unwrap();
+    ///
+    /// owned_archive.verify_layout_compat::<New, Error>().unwrap();
+    /// // SAFETY: The bytes were just checked to be a valid `New`.
+    /// let owned_archive = unsafe { owned_archive.rewrap::<New>() };
+    /// assert_eq!(owned_archive.renamed, 2);
+    /// ```
+    pub fn verify_layout_compat<U, E>(&self) -> Result<(), E>
+    where
+        U: Archive,
+        U::Archived: ValidateArchive<E>,
+        E: rkyv::rancor::Source,
+    {
+        validate_root::<U::Archived, E>(self.as_bytes(), None)
+    }
+
+    /// Reinterprets the bytes of this archive as a slice of archived `U`s.
+    ///
+    /// This suits buffers of fixed-size records packed back to back. The
+    /// number of records is the length of the buffer divided by the size of
+    /// `U::Archived`, and every record is validated.
+    ///
+    /// # Example
+    /// ```
+    /// use rkyv::rancor::Error;
+    /// use rkyv_util::owned::OwnedArchive;
+    ///
+    /// #[derive(rkyv::Archive, rkyv::Serialize)]
+    /// pub struct Record {
+    ///     id: u32,
+    /// }
+    ///
+    /// let records = [Record { id: 1 }, Record { id: 2 }];
+    /// let bytes = rkyv::to_bytes::<Error>(&records).unwrap();
+    /// let owned_archive =
+    ///     OwnedArchive::<[Record; 2], _>::new::<Error>(bytes).unwrap();
+    ///
+    /// let records = owned_archive.as_slice_of::<Record, Error>().unwrap();
+    /// assert_eq!(records[1].id, 2);
+    /// ```
+    pub fn as_slice_of<U, E>(&self) -> Result<&[U::Archived], E>
+    where
+        U: Archive,
+        U::Archived: ValidateArchive<E>,
+        E: rkyv::rancor::Source,
+    {
+        let bytes = self.as_bytes();
+        let size = core::mem::size_of::<U::Archived>();
+        if size == 0 || !bytes.len().is_multiple_of(size) {
+            return Err(E::new(RecordSizeMismatch {
+                len: bytes.len(),
+                size,
+            }));
+        }
+
+        let count = bytes.len() / size;
+        for i in 0..count {
+            rkyv::api::high::access_pos::<U::Archived, E>(bytes, i * size)?;
+        }
+        if count == 0 {
+            return Ok(&[]);
+        }
+        // # Safety
+        // Every record in the buffer was just validated, which includes
+        // checking that the first one, and so the slice, is aligned.
+        Ok(
+            unsafe {
+                core::slice::from_raw_parts(bytes.as_ptr().cast(), count)
+            },
+        )
+    }
+
+    /// Converts the container with a fallible function, validating the
+    /// archive again in the new container.
+    ///
+    /// `f` takes ownership of the container, so it is not recoverable if the
+    /// conversion fails. Conversions that can hand it back should include it
+    /// in their error. Validation failures in the new container are reported
+    /// through the same error type.
+    ///
+    /// # Example
+    /// ```
+    /// use std::sync::Arc;
+    ///
+    /// use rkyv::rancor::Error;
+    /// use rkyv_util::owned::OwnedArchive;
+    ///
+    /// #[derive(rkyv::Archive, rkyv::Serialize)]
+    /// pub struct Test {
+    ///     hello: u8,
+    /// }
+    ///
+    /// let bytes = rkyv::to_bytes::<Error>(&Test { hello: 2 }).unwrap();
+    /// let owned_archive =
+    ///     OwnedArchive::<Test, _>::new::<Error>(bytes).unwrap();
+    ///
+    /// let shared = owned_archive
+    ///     .try_map_container::<Arc<[u8]>, Error>(|bytes| {
+    ///         Ok(Arc::from(bytes.as_slice()))
+    ///     })
+    ///     .unwrap();
+    /// assert_eq!(shared.hello, 2);
+    /// ```
+    pub fn try_map_container<C2, E>(
+        self,
+        f: impl FnOnce(C) -> Result<C2, E>,
+    ) -> Result<OwnedArchive<T, C2>, E>
+    where
+        T: Archive,
+        T::Archived: ValidateArchive<E>,
+        E: rkyv::rancor::Source,
+        C2: StableBytes,
+    {
+        OwnedArchive::new::<E>(f(self.container)?)
+    }
+
+    /// Gets a range of the archive's raw bytes, or `None` if `range` is out
+    /// of bounds.
+    ///
+    /// Unlike [`byte_range_of`](Self::byte_range_of), this goes from a range
+    /// to bytes, for handing part of the buffer to code that treats it as
+    /// opaque.
+    ///
+    /// # Example
+    /// ```
+    /// use rkyv::rancor::Error;
+    /// use rkyv_util::owned::OwnedArchive;
+    ///
+    /// let bytes = rkyv::to_bytes::<Error>(&[1u8, 2, 3, 4]).unwrap();
+    /// let owned_archive =
+    ///     OwnedArchive::<[u8; 4], _>::new::<Error>(bytes).unwrap();
+    ///
+    /// assert_eq!(owned_archive.byte_view(1..3), Some(&[2, 3][..]));
+    /// assert_eq!(owned_archive.byte_view(2..5), None);
+    /// ```
+    pub fn byte_view(&self, range: Range<usize>) -> Option<&[u8]> {
+        self.as_bytes().get(range)
+    }
+
+    /// Gets the range of bytes within the buffer occupied by `field`.
+    ///
+    /// `field` should be a reference obtained by dereferencing this archive,
+    /// for example `&archive.some_field`. The range can then be used to
+    /// inspect or persist just that part of the buffer.
+    ///
+    /// # Panics
+    /// Panics if `field` does not lie within this archive's buffer.
+    ///
+    /// # Example
+    /// ```
+    /// use rkyv::rancor::Error;
+    /// use rkyv_util::owned::OwnedArchive;
+    ///
+    /// #[derive(rkyv::Archive, rkyv::Serialize)]
+    /// pub struct Test {
+    ///     hello: u8,
+    ///     world: u32,
+    /// }
+    ///
+    /// let bytes =
+    ///     rkyv::to_bytes::<Error>(&Test { hello: 2, world: 3 }).unwrap();
+    ///
+    /// let owned_archive =
+    ///     OwnedArchive::<Test, _>::new::<Error>(bytes).unwrap();
+    /// let range = owned_archive.byte_range_of(&owned_archive.world);
+    /// assert_eq!(range.len(), 4);
+    /// ```
+    pub fn byte_range_of<F>(&self, field: &F) -> Range<usize> {
+        let bytes = self.container.bytes().as_ptr_range();
+        let start = field as *const F as usize;
+        let end = start + size_of::<F>();
+        assert!(
+            start >= bytes.start as usize && end <= bytes.end as usize,
+            "field does not lie within the archive's buffer",
+        );
+
+        let base = bytes.start as usize;
+        start - base..end - base
+    }
+
+    /// Returns whether the root at the end of the buffer meets the alignment
+    /// of `T::Archived`.
+    ///
+    /// This is the same check validation makes, so it only fails for archives
+    /// created with [`new_unchecked`](Self::new_unchecked). The start of the
+    /// buffer does not have to be aligned.
+    pub fn is_aligned(&self) -> bool
+    where
+        T: Archive,
+    {
+        !matches!(
+            check_root::<T::Archived>(self.as_bytes()),
+            Err(ValidationIssue::Unaligned { .. })
+        )
+    }
+
+    /// Asserts that the root at the end of the buffer meets the alignment of
+    /// `T::Archived`.
+    ///
+    /// This only checks in debug builds and does nothing in release builds.
+    #[track_caller]
+    pub fn assert_aligned(&self)
+    where
+        T: Archive,
+    {
+        // The root can only be misaligned if the buffer is long enough to
+        // hold it, so the message never underflows.
+        debug_assert!(
+            self.is_aligned(),
+            "archive root at {:p} is not aligned to {} bytes",
+            self.as_bytes()[self.len() - size_of::<T::Archived>()..].as_ptr(),
+            align_of::<T::Archived>(),
+        );
+    }
+}
+
+impl<T, const A: usize> OwnedArchive<T, AlignedVec<A>> {
+    /// Creates a new `OwnedArchive` from a copy of `bytes` in a buffer
+    /// aligned to `A` bytes.
+    ///
+    /// This is for archives serialized with a larger alignment than the 16
+    /// bytes `AlignedVec` provides by default, or for bytes held in a buffer
+    /// with no alignment guarantee at all.
+    ///
+    /// # Example
+    /// ```
+    /// use rkyv::{rancor::Error, util::AlignedVec};
+    /// use rkyv_util::owned::OwnedArchive;
+    ///
+    /// #[derive(rkyv::Archive, rkyv::Serialize)]
+    /// pub struct Test {
+    ///     hello: u8,
+    /// }
+    ///
+    /// let bytes = rkyv::to_bytes::<Error>(&Test { hello: 2 }).unwrap();
+    ///
+    /// let owned_archive: OwnedArchive<Test, AlignedVec<32>> =
+    ///     OwnedArchive::new_aligned_to::<Error>(&bytes).unwrap();
+    /// assert_eq!(owned_archive.hello, 2);
+    /// assert_eq!(owned_archive.as_ptr() as usize % 32, 0);
+    /// ```
+    pub fn new_aligned_to<E>(bytes: &[u8]) -> Result<Self, E>
+    where
+        T: Archive,
+        T::Archived: ValidateArchive<E>,
+        E: rkyv::rancor::Source,
+    {
+        Self::new::<E>(AlignedVec::<A>::from_bytes(bytes))
+    }
+}
+
+impl<T> OwnedArchive<T, BackedBytes> {
+    /// Creates a new `OwnedArchive` from a `Vec<u8>`, copying it only if it
+    /// is not aligned.
+    ///
+    /// A `Vec<u8>` whose bytes happen to be aligned like an `AlignedVec`'s
+    /// is validated in place. Otherwise, the bytes are copied into an
+    /// `AlignedVec` first, so the archive doesn't fail validation depending
+    /// on where the allocator put them.
+    ///
+    /// # Example
+    /// ```
+    /// use rkyv::rancor::Error;
+    /// use rkyv_util::owned::OwnedArchive;
+    ///
+    /// #[derive(rkyv::Archive, rkyv::Serialize)]
+    /// pub struct Test {
+    ///     hello: u64,
+    /// }
+    ///
+    /// let bytes = rkyv::to_bytes::<Error>(&Test { hello: 2 }).unwrap();
+    /// let owned_archive =
+    ///     OwnedArchive::<Test, _>::from_vec::<Error>(bytes.to_vec()).unwrap();
+    /// assert_eq!(owned_archive.hello, 2);
+    /// ```
+    pub fn from_vec<E>(vec: Vec<u8>) -> Result<Self, E>
+    where
+        T: Archive,
+        T::Archived: ValidateArchive<E>,
+        E: rkyv::rancor::Source,
+    {
+        Self::new::<E>(BackedBytes::from_vec(vec, AlignedVec::<16>::ALIGNMENT))
+    }
+}
+
+/// Bytes that were either kept in their `Vec<u8>` or copied to be aligned.
+///
+/// This is the container used by [`OwnedArchive::from_vec`].
+#[derive(Clone, Debug)]
+pub enum BackedBytes {
+    /// The `Vec<u8>` was already aligned and is used as is.
+    Vec(Vec<u8>),
+    /// The `Vec<u8>` was misaligned, so its bytes were copied.
+    Aligned(AlignedVec),
+}
+
+impl BackedBytes {
+    /// Keeps `vec` if its bytes are aligned to `align`, and copies them into
+    /// an `AlignedVec` otherwise.
+    fn from_vec(vec: Vec<u8>, align: usize) -> Self {
+        if (vec.as_ptr() as usize).is_multiple_of(align) {
+            Self::Vec(vec)
+        } else {
+            Self::Aligned(AlignedVec::from_bytes(&vec))
+        }
+    }
+}
+
+impl Deref for BackedBytes {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            Self::Vec(vec) => vec,
+            Self::Aligned(vec) => vec,
+        }
+    }
+}
+
+impl<T> OwnedArchive<T, AlignedVec> {
+    /// Reads exactly `len` bytes from `reader` and validates them as an
+    /// archive.
+    ///
+    /// This is for streams that carry an archive of known length, for example
+    /// from a length prefix, followed by other data. On success, `reader` is
+    /// left positioned right after the archive. Reaching the end of the
+    /// stream before `len` bytes have been read is an error.
+    ///
+    /// `len` is not trusted to size the buffer up front, so a corrupt or
+    /// hostile length prefix only costs as much memory as the stream
+    /// actually holds.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::Read;
+    ///
+    /// use rkyv::rancor::Error;
+    /// use rkyv_util::owned::OwnedArchive;
+    ///
+    /// #[derive(rkyv::Archive, rkyv::Serialize)]
+    /// pub struct Test {
+    ///     hello: u8,
+    /// }
+    ///
+    /// let bytes = rkyv::to_bytes::<Error>(&Test { hello: 2 }).unwrap();
+    /// let mut stream = [bytes.as_slice(), b"rest"].concat();
+    /// let mut reader = stream.as_slice();
+    ///
+    /// let owned_archive = OwnedArchive::<Test, _>::from_reader_exact::<
+    ///     _,
+    ///     Error,
+    /// >(&mut reader, bytes.len())
+    /// .unwrap();
+    /// assert_eq!(owned_archive.hello, 2);
+    /// assert_eq!(reader, b"rest");
+    /// ```
+    pub fn from_reader_exact<R, E>(
+        reader: &mut R,
+        len: usize,
+    ) -> Result<Self, E>
+    where
+        R: Read,
+        T: Archive,
+        T::Archived: ValidateArchive<E>,
+        E: rkyv::rancor::Source,
+    {
+        let mut bytes = AlignedVec::new();
+        bytes
+            .extend_from_reader(&mut reader.take(len as u64))
+            .map_err(E::new)?;
+        if bytes.len() != len {
+            return Err(E::new(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                format!(
+                    "stream ended after {} of {len} archive bytes",
+                    bytes.len()
+                ),
+            )));
+        }
+        Self::new::<E>(bytes)
+    }
+}
+
+impl<const N: usize, C: StableBytes> OwnedArchive<[u8; N], C> {
+    /// Gets the archived byte array.
+    ///
+    /// Byte arrays archive as themselves, so this is the array stored at the
+    /// root of the archive.
+    ///
+    /// # Example
+    /// ```
+    /// use rkyv::rancor::Error;
+    /// use rkyv_util::owned::OwnedArchive;
+    ///
+    /// let bytes = rkyv::to_bytes::<Error>(&[7u8; 32]).unwrap();
+    /// let owned_archive =
+    ///     OwnedArchive::<[u8; 32], _>::new::<Error>(bytes).unwrap();
+    /// assert_eq!(owned_archive.as_archived_slice(), &[7u8; 32]);
+    /// ```
+    pub fn as_archived_slice(&self) -> &[u8; N] {
+        self.deref()
+    }
+}
+
+impl<K, V, S, C> OwnedArchive<HashMap<K, V, S>, C>
+where
+    K: Archive + Hash + Eq,
+    K::Archived: Hash + Eq,
+    V: Archive,
+    C: StableBytes,
+{
+    /// Looks up the archived value for `key` in the archived map.
+    ///
+    /// As with `HashMap::get`, `key` may be any borrowed form of the archived
+    /// key type, such as a `str` for archived `String` keys.
+    ///
+    /// # Example
+    /// ```
+    /// use std::collections::HashMap;
+    ///
+    /// use rkyv::rancor::Error;
+    /// use rkyv_util::owned::OwnedArchive;
+    ///
+    /// let map = HashMap::from([("hello".to_string(), 2u8)]);
+    /// let bytes = rkyv::to_bytes::<Error>(&map).unwrap();
+    /// let owned_archive = OwnedArchive::<HashMap<String, u8>, _>::new::<
+    ///     Error,
+    /// >(bytes)
+    /// .unwrap();
+    ///
+    /// assert_eq!(owned_archive.get_key("hello"), Some(&2));
+    /// assert_eq!(owned_archive.get_key("world"), None);
+    /// ```
+    pub fn get_key<Q>(&self, key: &Q) -> Option<&V::Archived>
+    where
+        K::Archived: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.deref().get(key)
+    }
+}
+
+impl<K, V, C> OwnedArchive<BTreeMap<K, V>, C>
+where
+    K: Archive + Ord,
+    K::Archived: Ord,
+    V: Archive,
+    C: StableBytes,
+{
+    /// Iterates over the entries of the archived map, sorted by key.
+    ///
+    /// # Example
+    /// ```
+    /// use std::collections::BTreeMap;
+    ///
+    /// use rkyv::rancor::Error;
+    /// use rkyv_util::owned::OwnedArchive;
+    ///
+    /// let map = BTreeMap::from([(2u8, 20u8), (1, 10)]);
+    /// let bytes = rkyv::to_bytes::<Error>(&map).unwrap();
+    /// let owned_archive =
+    ///     OwnedArchive::<BTreeMap<u8, u8>, _>::new::<Error>(bytes).unwrap();
+    ///
+    /// let keys = owned_archive.iter().map(|(k, _)| *k).collect::<Vec<_>>();
+    /// assert_eq!(keys, [1, 2]);
+    /// ```
+    pub fn iter(
+        &self,
+    ) -> impl Iterator<Item = (&K::Archived, &V::Archived)> + '_ {
+        self.deref().iter()
+    }
+}
+
+impl<A, B, C> OwnedArchive<(A, B), C>
+where
+    A: Archive,
+    B: Archive,
+    C: StableBytes,
+{
+    /// Deserializes both halves of an archived pair.
+    ///
+    /// Each component must be deserializable from its archived form with
+    /// rkyv's high-level deserializer, as for
+    /// [`deserialize`](OwnedArchive::deserialize). This is the usual shape
+    /// of key/value records.
+    ///
+    /// # Example
+    /// ```
+    /// use rkyv::rancor::Error;
+    /// use rkyv_util::owned::OwnedArchive;
+    ///
+    /// let record = (1u32, "one".to_string());
+    /// let bytes = rkyv::to_bytes::<Error>(&record).unwrap();
+    /// let owned_archive =
+    ///     OwnedArchive::<(u32, String), _>::new::<Error>(bytes).unwrap();
+    ///
+    /// let (key, value) = owned_archive.to_owned_tuple::<Error>().unwrap();
+    /// assert_eq!((key, value.as_str()), (1, "one"));
+    /// ```
+    pub fn to_owned_tuple<E>(&self) -> Result<(A, B), E>
+    where
+        A::Archived: Deserialize<A, HighDeserializer<E>>,
+        B::Archived: Deserialize<B, HighDeserializer<E>>,
+        E: rkyv::rancor::Source,
+    {
+        self.deserialize::<E>()
+    }
+}
+
+impl<C: StableBytes> OwnedArchive<String, C> {
+    /// Gets the archived string as a `str`.
+    ///
+    /// Validation has already checked that the string is valid UTF-8.
+    ///
+    /// # Example
+    /// ```
+    /// use rkyv::rancor::Error;
+    /// use rkyv_util::owned::OwnedArchive;
+    ///
+    /// let bytes = rkyv::to_bytes::<Error>(&"hello".to_string()).unwrap();
+    /// let owned_archive =
+    ///     OwnedArchive::<String, _>::new::<Error>(bytes).unwrap();
+    /// assert_eq!(owned_archive.as_str(), "hello");
+    /// ```
+    pub fn as_str(&self) -> &str {
+        self.deref().as_str()
+    }
+}
+
+impl<T: Archive, C: StableBytes> OwnedArchive<Vec<T>, C> {
+    /// Splits the archived elements into two slices at `mid`.
+    ///
+    /// The first slice holds the elements before `mid` and the second holds
+    /// the rest. Both borrow from this archive's buffer.
+    ///
+    /// # Panics
+    /// Panics if `mid` is greater than the number of elements.
+    ///
+    /// # Example
+    /// ```
+    /// use rkyv::rancor::Error;
+    /// use rkyv_util::owned::OwnedArchive;
+    ///
+    /// let bytes = rkyv::to_bytes::<Error>(&vec![1u32, 2, 3]).unwrap();
+    /// let owned_archive =
+    ///     OwnedArchive::<Vec<u32>, _>::new::<Error>(bytes).unwrap();
+    ///
+    /// let (left, right) = owned_archive.split_at(1);
+    /// assert_eq!(left, [1]);
+    /// assert_eq!(right, [2, 3]);
+    /// ```
+    pub fn split_at(&self, mid: usize) -> (&[T::Archived], &[T::Archived]) {
+        self.as_slice().split_at(mid)
+    }
+
+    /// Maps every archived element to an owned value.
+    ///
+    /// # Example
+    /// ```
+    /// use rkyv::rancor::Error;
+    /// use rkyv_util::owned::OwnedArchive;
+    ///
+    /// #[derive(rkyv::Archive, rkyv::Serialize)]
+    /// pub struct Test {
+    ///     hello: u8,
+    /// }
+    ///
+    /// let tests = vec![Test { hello: 1 }, Test { hello: 2 }];
+    /// let bytes = rkyv::to_bytes::<Error>(&tests).unwrap();
+    /// let owned_archive =
+    ///     OwnedArchive::<Vec<Test>, _>::new::<Error>(bytes).unwrap();
+    ///
+    /// assert_eq!(owned_archive.map_all(|test| test.hello), [1, 2]);
+    /// ```
+    pub fn map_all<U, F>(&self, f: F) -> Vec<U>
+    where
+        F: FnMut(&T::Archived) -> U,
+    {
+        self.iter().map(f).collect()
+    }
+
+    /// Iterates over the archived elements that match `pred`.
+    ///
+    /// The elements are borrowed from this archive's buffer, so nothing is
+    /// deserialized or copied.
+    ///
+    /// # Example
+    /// ```
+    /// use rkyv::rancor::Error;
+    /// use rkyv_util::owned::OwnedArchive;
+    ///
+    /// #[derive(rkyv::Archive, rkyv::Serialize)]
+    /// pub struct Test {
+    ///     hello: u8,
+    /// }
+    ///
+    /// let tests = vec![Test { hello: 1 }, Test { hello: 2 }];
+    /// let bytes = rkyv::to_bytes::<Error>(&tests).unwrap();
+    /// let owned_archive =
+    ///     OwnedArchive::<Vec<Test>, _>::new::<Error>(bytes).unwrap();
+    ///
+    /// let mut even = owned_archive.scan(|test| test.hello % 2 == 0);
+    /// assert_eq!(even.next().map(|test| test.hello), Some(2));
+    /// assert!(even.next().is_none());
+    /// ```
+    pub fn scan<P>(&self, mut pred: P) -> impl Iterator<Item = &T::Archived>
+    where
+        P: FnMut(&T::Archived) -> bool,
+    {
+        self.iter().filter(move |element| pred(element))
+    }
+}
+
+impl<T: Archive, C: StableBytesMut> OwnedArchive<Vec<T>, C>
+where
+    T::Archived: Portable,
+{
+    /// Iterates over the archived elements, sealed for mutation.
+    ///
+    /// Each element is yielded exactly once, so the seals never alias.
+    ///
+    /// # Example
+    /// ```
+    /// use rkyv::{munge::munge, rancor::Error};
+    /// use rkyv_util::owned::OwnedArchive;
+    ///
+    /// #[derive(rkyv::Archive, rkyv::Serialize)]
+    /// pub struct Test {
+    ///     hits: u32,
+    /// }
+    ///
+    /// let records = vec![Test { hits: 1 }, Test { hits: 2 }];
+    /// let bytes = rkyv::to_bytes::<Error>(&records).unwrap();
+    /// let mut owned_archive =
+    ///     OwnedArchive::<Vec<Test>, _>::new::<Error>(bytes).unwrap();
+    ///
+    /// for record in owned_archive.iter_mut() {
+    ///     munge!(let ArchivedTest { mut hits } = record);
+    ///     *hits += 1;
+    /// }
+    /// assert_eq!(owned_archive[1].hits, 3);
+    /// ```
+    pub fn iter_mut(
+        &mut self,
+    ) -> impl ExactSizeIterator<Item = Seal<'_, T::Archived>> {
+        let elements = ArchivedVec::as_slice_seal(self.get_mut());
+        // # Safety
+        // Every element is immediately sealed again, so nothing is ever
+        // moved out of the slice or exposed without a seal.
+        unsafe { elements.unseal_unchecked() }
+            .iter_mut()
+            .map(Seal::new)
+    }
+}
+
+impl<C: StableBytes, T: Archive> Deref for OwnedArchive<T, C> {
+    type Target = T::Archived;
+
+    fn deref(&self) -> &Self::Target {
+        // # Safety
+        // Here we can safely access the underlying archive. This is
+        // because `StableBytes` enforces the safety contract that the
+        // underlying bytes remain stable, and thus the container that
+        // we took ownership of when creating the `OwnedArchive` has
+        // already been created.
+        unsafe { access_root(self.container.bytes()) }
+    }
+}
+
+impl<T, C: Clone> Clone for OwnedArchive<T, C> {
+    fn clone(&self) -> Self {
+        Self {
+            container: self.container.clone(),
+            _type: self._type,
+        }
+    }
+}
+
+impl<T> Copy for OwnedArchive<T, &[u8]> {}
+
+impl<T: Archive, C: StableBytes> Debug for OwnedArchive<T, C>
+where
+    T::Archived: Debug,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        self.deref().fmt(f)
+    }
+}
+
+/// Hashes the archived value, not the raw bytes.
+///
+/// The hash is as stable as `T::Archived`'s own `Hash` impl. rkyv's archived
+/// primitives hash their native value, so hashes do not depend on the
+/// endianness of the archive, and archived strings and vectors hash like
+/// `str` and slices. Whether a hash is stable across runs and hosts then only
+/// depends on the `Hasher`: use a deterministic one for content addressing,
+/// as `std`'s default hasher is randomly seeded.
+impl<T: Archive, C: StableBytes> Hash for OwnedArchive<T, C>
+where
+    T::Archived: Hash,
+{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.deref().hash(state)
+    }
+}
+
+/// A contract guaranteeing that bytes should originate
+/// from the same source between accesses.
+///
+/// # Safety
+/// - `bytes` always refers to the same buffer.
+/// - The **only** way to edit the underlying bytes is through `StableBytesMut`
+///
+/// For instance, the following malicious implementation would be unsafe:
+/// ```
+/// use std::cell::RefCell;
+///
+/// use rkyv_util::owned::StableBytes;
+///
+/// struct Malicious {
+///     counter: RefCell<u8>,
+/// }
+///
+/// unsafe impl StableBytes for Malicious {
+///     fn bytes(&self) -> &[u8] {
+///         *self.counter.borrow_mut() += 1;
+///         if *self.counter.borrow() % 2 == 0 {
+///             &[0x00]
+///         } else {
+///             &[0x01]
+///         }
+///     }
+/// }
+/// ```
+/// The above code does not meet the safety contract because
+/// every other access will return a different set of bytes and it is not
+/// referencing a constant buffer.
+///
+/// Another example for possible unsafety is as follows:
+/// ```
+/// use rkyv_util::owned::StableBytes;
+///
+/// struct Good {
+///     data: Vec<u8>,
+/// }
+///
+/// unsafe impl StableBytes for Good {
+///     fn bytes(&self) -> &[u8] {
+///         self.data.as_ref()
+///     }
+/// }
+/// ```
+/// The above implementation is only safe if the `Vec<u8>` inside
+/// of `Good` is only ever mutated through `StableBytesMut`.
+/// Otherwise the bytes could be changed.
+///
+/// An example that is always safe would be the following:
+/// ```
+/// use std::sync::Arc;
+///
+/// use rkyv_util::owned::StableBytes;
+///
+/// struct Good {
+///     data: Arc<[u8]>,
+/// }
+///
+/// unsafe impl StableBytes for Good {
+///     fn bytes(&self) -> &[u8] {
+///         self.data.as_ref()
+///     }
+/// }
+/// ```
+/// Since the data within the `Arc<[u8]>` is not mutable, we can never change
+/// it and thus this is necessarily stable.
+pub unsafe trait StableBytes {
+    /// Gets the underlying bytes.
+    fn bytes(&self) -> &[u8];
+}
+
+/// A contract guaranteeing that bytes should originate
+/// from the same source between acceses.
+///
+/// # Safety
+/// - `bytes` always refers to the same buffer.
+/// - The **only** way to edit the underlying bytes is through `StableBytesMut`
+///
+/// For instance, the following malicious implementation would be unsafe:
+/// ```
+/// use std::cell::RefCell;
+///
+/// use rkyv_util::owned::{StableBytes, StableBytesMut};
+///
+/// struct Malicious {
+///     counter: RefCell<u8>,
+///     buf_one: Vec<u8>,
+///     buf_two: Vec<u8>,
+/// }
+///
+/// unsafe impl StableBytes for Malicious {
+///     fn bytes(&self) -> &[u8] {
+///         self.buf_one.as_ref()
+///     }
+/// }
+///
+/// unsafe impl StableBytesMut for Malicious {
+///     fn bytes_mut(&mut self) -> &mut [u8] {
+///         *self.counter.borrow_mut() += 1;
+///         if *self.counter.borrow() % 2 == 0 {
+///             &mut self.buf_one
+///         } else {
+///             &mut self.buf_two
+///         }
+///     }
+/// }
+/// ```
+/// The above code does not meet the safety contract because
+/// every other access will return a different set of bytes and it is not
+/// referencing a constant buffer.
+///
+/// Another example for possible unsafety is as follows:
+/// ```
+/// use rkyv_util::owned::{StableBytes, StableBytesMut};
+///
 /// struct Good {
 ///     data: Vec<u8>,
 /// }
@@ -186,199 +2539,2070 @@ where
 ///         self.data.as_ref()
 ///     }
 /// }
+///
+/// unsafe impl StableBytesMut for Good {
+///     fn bytes_mut(&mut self) -> &mut [u8] {
+///         self.data.as_mut()
+///     }
+/// }
 /// ```
 /// The above implementation is only safe if the `Vec<u8>` inside
 /// of `Good` is only ever mutated through `StableBytesMut`.
 /// Otherwise the bytes could be changed.
+pub unsafe trait StableBytesMut: StableBytes {
+    /// Gets the underlying bytes mutably.
+    fn bytes_mut(&mut self) -> &mut [u8];
+}
+
+// ==============
+// Implementations of `StableBytes` for popular types
+// ==============
+
+unsafe impl<const A: usize> StableBytesMut for AlignedVec<A> {
+    fn bytes_mut(&mut self) -> &mut [u8] {
+        self.as_mut()
+    }
+}
+
+unsafe impl<const A: usize> StableBytes for AlignedVec<A> {
+    fn bytes(&self) -> &[u8] {
+        self.as_ref()
+    }
+}
+
+// `Vec<u8>`, `Box<[u8]>`, `Arc<[u8]>` and `Rc<[u8]>` carry no alignment
+// guarantee. That is still sound because `OwnedArchive::new` validates the
+// alignment of the archive, but it means construction can fail depending on
+// where the allocator placed the bytes.
+
+unsafe impl StableBytesMut for Vec<u8> {
+    fn bytes_mut(&mut self) -> &mut [u8] {
+        self.as_mut()
+    }
+}
+
+unsafe impl StableBytes for Vec<u8> {
+    fn bytes(&self) -> &[u8] {
+        self.as_ref()
+    }
+}
+
+unsafe impl StableBytes for Arc<[u8]> {
+    fn bytes(&self) -> &[u8] {
+        self.as_ref()
+    }
+}
+
+// A shared container can't reach `StableBytesMut`, so its bytes are frozen.
+unsafe impl<C: StableBytes> StableBytes for Arc<C> {
+    fn bytes(&self) -> &[u8] {
+        C::bytes(self)
+    }
+}
+
+unsafe impl StableBytes for Rc<[u8]> {
+    fn bytes(&self) -> &[u8] {
+        self.as_ref()
+    }
+}
+
+unsafe impl StableBytesMut for Box<[u8]> {
+    fn bytes_mut(&mut self) -> &mut [u8] {
+        self.as_mut()
+    }
+}
+
+unsafe impl StableBytes for Box<[u8]> {
+    fn bytes(&self) -> &[u8] {
+        self.as_ref()
+    }
+}
+
+unsafe impl StableBytes for &[u8] {
+    fn bytes(&self) -> &[u8] {
+        self
+    }
+}
+
+// The archive covers the whole `Vec` in a cursor, regardless of the cursor's
+// position. Once wrapped, the cursor can't be written through since the
+// archive owns it.
+
+unsafe impl StableBytes for io::Cursor<Vec<u8>> {
+    fn bytes(&self) -> &[u8] {
+        self.get_ref().as_slice()
+    }
+}
+
+// A mutable borrow gives the archive exclusive access to the `Vec` until the
+// borrow ends, so nothing else can change or reallocate it in the meantime.
+
+unsafe impl StableBytesMut for &mut Vec<u8> {
+    fn bytes_mut(&mut self) -> &mut [u8] {
+        self.as_mut()
+    }
+}
+
+unsafe impl StableBytes for &mut Vec<u8> {
+    fn bytes(&self) -> &[u8] {
+        self.as_ref()
+    }
+}
+
+// Both variants are only ever changed through `StableBytesMut`.
+
+unsafe impl StableBytesMut for BackedBytes {
+    fn bytes_mut(&mut self) -> &mut [u8] {
+        match self {
+            Self::Vec(vec) => vec,
+            Self::Aligned(vec) => vec,
+        }
+    }
+}
+
+unsafe impl StableBytes for BackedBytes {
+    fn bytes(&self) -> &[u8] {
+        self
+    }
+}
+
+/// Validates `bytes` as an archive of `U` rooted at its very end.
+///
+/// This is what `rkyv::access` checks, except that only the root has to be
+/// aligned; in debug builds rkyv also asserts that the start of the buffer
+/// is. Every validating constructor goes through here, so they all agree
+/// with [`diagnose`] on which buffers are valid.
+fn validate_root<U, E>(
+    bytes: &[u8],
+    max_depth: Option<NonZeroUsize>,
+) -> Result<(), E>
+where
+    U: ValidateArchive<E>,
+    E: rkyv::rancor::Source,
+{
+    check_alignment::<U, E>(bytes)?;
+    let mut validator = Validator::new(
+        ArchiveValidator::with_max_depth(bytes, max_depth),
+        SharedValidator::new(),
+    );
+    rkyv::api::check_pos_with_context::<U, _, E>(
+        bytes,
+        rkyv::api::root_position::<U>(bytes.len()),
+        &mut validator,
+    )
+}
+
+/// Gets the root of an archive of `U` at the very end of `bytes`.
+///
+/// # Safety
+/// `bytes` must pass [`validate_root`] for `U`.
+pub(crate) unsafe fn access_root<U: Portable>(bytes: &[u8]) -> &U {
+    let pos = rkyv::api::root_position::<U>(bytes.len());
+    // # Safety
+    // The caller guarantees that a valid, aligned `U` sits at `pos`.
+    unsafe { &*bytes.as_ptr().add(pos).cast::<U>() }
+}
+
+/// Gets the root of an archive of `U` at the very end of `bytes`, sealed
+/// for mutation.
 ///
-/// An example that is always safe would be the following:
-/// ```
-/// use std::sync::Arc;
+/// # Safety
+/// `bytes` must pass [`validate_root`] for `U`.
+unsafe fn access_root_mut<U: Portable>(bytes: &mut [u8]) -> Seal<'_, U> {
+    let pos = rkyv::api::root_position::<U>(bytes.len());
+    // # Safety
+    // The caller guarantees that a valid, aligned `U` sits at `pos`.
+    unsafe { Seal::new(&mut *bytes.as_mut_ptr().add(pos).cast::<U>()) }
+}
+
+/// Rejects a buffer whose root, a `U` at its very end, is misaligned.
 ///
-/// use rkyv_util::owned::StableBytes;
+/// Buffers too short for a `U` are left for validation to report.
+fn check_alignment<U, E>(bytes: &[u8]) -> Result<(), E>
+where
+    E: rkyv::rancor::Source,
+{
+    match check_root::<U>(bytes) {
+        Err(ValidationIssue::Unaligned { align }) => Err(E::new(Unaligned {
+            addr: bytes[bytes.len() - size_of::<U>()..].as_ptr() as usize,
+            align,
+        })),
+        _ => Ok(()),
+    }
+}
+
+#[derive(Debug)]
+struct Unaligned {
+    addr: usize,
+    align: usize,
+}
+
+impl core::fmt::Display for Unaligned {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "archive root at {:#x} is unaligned, it must be aligned to {} \
+             bytes; copy it into an aligned buffer with \
+             `OwnedArchive::new_aligned_to`",
+            self.addr, self.align,
+        )
+    }
+}
+
+impl Error for Unaligned {}
+
+#[cfg(feature = "rayon")]
+#[derive(Debug)]
+struct ChecksumMismatch {
+    expected: u64,
+    actual: u64,
+}
+
+#[cfg(feature = "rayon")]
+impl core::fmt::Display for ChecksumMismatch {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "archive checksum {:#018x} does not match the expected {:#018x}",
+            self.actual, self.expected,
+        )
+    }
+}
+
+#[cfg(feature = "rayon")]
+impl Error for ChecksumMismatch {}
+
+#[derive(Debug)]
+struct RecordSizeMismatch {
+    len: usize,
+    size: usize,
+}
+
+impl core::fmt::Display for RecordSizeMismatch {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "buffer of {} bytes does not hold a whole number of {}-byte \
+             records",
+            self.len, self.size,
+        )
+    }
+}
+
+impl Error for RecordSizeMismatch {}
+
+/// A container that can be created by copying a byte slice.
 ///
-/// struct Good {
-///     data: Arc<[u8]>,
-/// }
+/// This is what [`OwnedArchive::clone_into_container`] uses to produce a copy
+/// of an archive with a different backing.
+pub trait FromBytes: StableBytes + Sized {
+    /// Creates a new container holding a copy of `bytes`.
+    fn from_bytes(bytes: &[u8]) -> Self;
+}
+
+impl<const A: usize> FromBytes for AlignedVec<A> {
+    fn from_bytes(bytes: &[u8]) -> Self {
+        let mut vec = AlignedVec::<A>::with_capacity(bytes.len());
+        vec.extend_from_slice(bytes);
+        vec
+    }
+}
+
+impl FromBytes for Vec<u8> {
+    fn from_bytes(bytes: &[u8]) -> Self {
+        bytes.to_vec()
+    }
+}
+
+impl FromBytes for Box<[u8]> {
+    fn from_bytes(bytes: &[u8]) -> Self {
+        bytes.into()
+    }
+}
+
+impl FromBytes for Arc<[u8]> {
+    fn from_bytes(bytes: &[u8]) -> Self {
+        bytes.into()
+    }
+}
+
+impl FromBytes for Rc<[u8]> {
+    fn from_bytes(bytes: &[u8]) -> Self {
+        bytes.into()
+    }
+}
+
+pub(crate) mod sealed {
+    pub trait Sealed {}
+}
+
+/// A built-in container whose mutability is known to this crate.
 ///
-/// unsafe impl StableBytes for Good {
-///     fn bytes(&self) -> &[u8] {
-///         self.data.as_ref()
-///     }
-/// }
-/// ```
-/// Since the data within the `Arc<[u8]>` is not mutable, we can never change
-/// it and thus this is necessarily stable.
-pub unsafe trait StableBytes {
-    /// Gets the underlying bytes.
-    fn bytes(&self) -> &[u8];
+/// This lets generic code attempt mutation and handle read-only containers
+/// at runtime instead of requiring `StableBytesMut` at compile time. It is
+/// sealed, so it is only implemented for the containers this crate provides
+/// `StableBytes` for. `AlignedVec`, `Vec<u8>`, `Box<[u8]>`, `&mut Vec<u8>`
+/// and [`BackedBytes`] are mutable, while `Arc<[u8]>`, `Rc<[u8]>`, `&[u8]`,
+/// `io::Cursor<Vec<u8>>` and an `Arc` of any of these are read-only.
+/// `HeaderedArchive<C>` is mutable exactly when `C` is, and with the `mmap`
+/// feature `MmapMut` is mutable and `SharedMmap` is read-only.
+pub trait ByteBacking: StableBytes + sealed::Sealed {
+    /// Whether the container implements `StableBytesMut`.
+    ///
+    /// Generic code can branch on this at compile time, instead of calling
+    /// [`try_bytes_mut`](Self::try_bytes_mut) to find out.
+    const MUTABLE: bool;
+
+    /// Gets the underlying bytes mutably, or `None` if the container is
+    /// read-only.
+    ///
+    /// This returns `Some` exactly when [`MUTABLE`](Self::MUTABLE) is true,
+    /// and then the bytes are the same ones that `bytes_mut` returns.
+    fn try_bytes_mut(&mut self) -> Option<&mut [u8]>;
+
+    /// Gets the underlying bytes as an `Arc`.
+    ///
+    /// This copies the bytes, except for containers that are already an
+    /// `Arc<[u8]>`, which are cloned instead.
+    fn to_arc(&self) -> Arc<[u8]> {
+        Arc::from(self.bytes())
+    }
+}
+
+macro_rules! impl_byte_backing {
+    (mut $($ty:ty),*) => {
+        $(
+            impl sealed::Sealed for $ty {}
+
+            impl ByteBacking for $ty {
+                const MUTABLE: bool = true;
+
+                fn try_bytes_mut(&mut self) -> Option<&mut [u8]> {
+                    Some(self.bytes_mut())
+                }
+            }
+        )*
+    };
+    ($($ty:ty),*) => {
+        $(
+            impl sealed::Sealed for $ty {}
+
+            impl ByteBacking for $ty {
+                const MUTABLE: bool = false;
+
+                fn try_bytes_mut(&mut self) -> Option<&mut [u8]> {
+                    None
+                }
+            }
+        )*
+    };
+}
+
+impl_byte_backing!(mut Vec<u8>, Box<[u8]>, &mut Vec<u8>, BackedBytes);
+impl_byte_backing!(Rc<[u8]>, &[u8], io::Cursor<Vec<u8>>);
+
+impl<const A: usize> sealed::Sealed for AlignedVec<A> {}
+
+impl<const A: usize> ByteBacking for AlignedVec<A> {
+    const MUTABLE: bool = true;
+
+    fn try_bytes_mut(&mut self) -> Option<&mut [u8]> {
+        Some(self.bytes_mut())
+    }
+}
+
+impl sealed::Sealed for Arc<[u8]> {}
+
+impl ByteBacking for Arc<[u8]> {
+    const MUTABLE: bool = false;
+
+    fn try_bytes_mut(&mut self) -> Option<&mut [u8]> {
+        None
+    }
+
+    fn to_arc(&self) -> Arc<[u8]> {
+        self.clone()
+    }
+}
+
+impl<C: ByteBacking> sealed::Sealed for Arc<C> {}
+
+impl<C: ByteBacking> ByteBacking for Arc<C> {
+    const MUTABLE: bool = false;
+
+    fn try_bytes_mut(&mut self) -> Option<&mut [u8]> {
+        None
+    }
 }
 
-/// A contract guaranteeing that bytes should originate
-/// from the same source between acceses.
-///
-/// # Safety
-/// - `bytes` always refers to the same buffer.
-/// - The **only** way to edit the underlying bytes is through `StableBytesMut`
-///
-/// For instance, the following malicious implementation would be unsafe:
-/// ```
-/// use std::cell::RefCell;
-///
-/// use rkyv_util::owned::{StableBytes, StableBytesMut};
-///
-/// struct Malicious {
-///     counter: RefCell<u8>,
-///     buf_one: Vec<u8>,
-///     buf_two: Vec<u8>,
-/// }
-///
-/// unsafe impl StableBytes for Malicious {
-///     fn bytes(&self) -> &[u8] {
-///         self.buf_one.as_ref()
-///     }
-/// }
-///
-/// unsafe impl StableBytesMut for Malicious {
-///     fn bytes_mut(&mut self) -> &mut [u8] {
-///         *self.counter.borrow_mut() += 1;
-///         if *self.counter.borrow() % 2 == 0 {
-///             &mut self.buf_one
-///         } else {
-///             &mut self.buf_two
-///         }
-///     }
-/// }
-/// ```
-/// The above code does not meet the safety contract because
-/// every other access will return a different set of bytes and it is not
-/// referencing a constant buffer.
-///
-/// Another example for possible unsafety is as follows:
-/// ```
-/// use rkyv_util::owned::{StableBytes, StableBytesMut};
-///
-/// struct Good {
-///     data: Vec<u8>,
-/// }
-///
-/// unsafe impl StableBytes for Good {
-///     fn bytes(&self) -> &[u8] {
-///         self.data.as_ref()
-///     }
-/// }
-///
-/// unsafe impl StableBytesMut for Good {
-///     fn bytes_mut(&mut self) -> &mut [u8] {
-///         self.data.as_mut()
-///     }
-/// }
-/// ```
-/// The above implementation is only safe if the `Vec<u8>` inside
-/// of `Good` is only ever mutated through `StableBytesMut`.
-/// Otherwise the bytes could be changed.
-pub unsafe trait StableBytesMut: StableBytes {
-    /// Gets the underlying bytes mutably.
-    fn bytes_mut(&mut self) -> &mut [u8];
-}
+#[cfg(test)]
+mod tests {
+    use core::num::NonZeroUsize;
+    use std::{rc::Rc, sync::Arc};
+
+    use rkyv::{
+        bytecheck::CheckBytes, munge::munge, rancor, util::AlignedVec, Archive,
+        Deserialize, Portable, Serialize,
+    };
+
+    use super::{
+        BackedBytes, BorrowedArchive, BorrowedMutArchive, ByteBacking,
+        OwnedArchive, StableBytes, ValidationIssue, ValidationLimits,
+        ValidationReport,
+    };
+
+    #[derive(Archive, Clone, PartialEq, Deserialize, Serialize, Debug)]
+    #[rkyv(compare(PartialEq), derive(Debug))]
+    pub struct ArchiveStub {
+        hello: u8,
+        world: u64,
+    }
+
+    /// A container whose bytes start one byte into an aligned buffer, like a
+    /// `Vec<u8>` that the allocator happened to misalign.
+    struct Misaligned(AlignedVec);
+
+    impl Misaligned {
+        fn new(bytes: &[u8]) -> Self {
+            let mut shifted = AlignedVec::<16>::new();
+            shifted.push(0);
+            shifted.extend_from_slice(bytes);
+            Self(shifted)
+        }
+    }
+
+    unsafe impl StableBytes for Misaligned {
+        fn bytes(&self) -> &[u8] {
+            &self.0[1..]
+        }
+    }
+
+    #[test]
+    fn test_owned_archive_vec() {
+        let stub = ArchiveStub { hello: 4, world: 5 };
+
+        let bytes = rkyv::to_bytes::<rancor::Error>(&stub).unwrap();
+        let owned: OwnedArchive<ArchiveStub, _> =
+            OwnedArchive::new::<rancor::Error>(bytes).unwrap();
+
+        // Finally check to see that both are equal.
+        assert_eq!(owned.hello, 4);
+        assert_eq!(owned.world, 5);
+
+        // Finally check to see that both are equal.
+        assert_eq!(stub, *owned);
+    }
+
+    #[test]
+    fn test_owned_archive_vec_mut() {
+        let stub = ArchiveStub { hello: 4, world: 5 };
+
+        let bytes = rkyv::to_bytes::<rancor::Error>(&stub).unwrap();
+        let mut owned: OwnedArchive<ArchiveStub, _> =
+            OwnedArchive::new::<rancor::Error>(bytes).unwrap();
+
+        // Check that they are the same.
+        assert_eq!(stub, *owned);
+
+        // Check mutability
+        munge!(let ArchivedArchiveStub { mut hello, ..} = owned.get_mut());
+        assert_eq!(*hello, 4);
+
+        *hello = 9;
+        assert_eq!(owned.hello, 9);
+    }
+
+    #[test]
+    fn test_owned_archive_new_boxed() {
+        let stub = ArchiveStub { hello: 4, world: 5 };
+
+        let bytes = rkyv::to_bytes::<rancor::Error>(&stub).unwrap();
+        let owned: OwnedArchive<ArchiveStub, _> =
+            OwnedArchive::new_boxed(bytes).unwrap();
+        assert_eq!(stub, *owned);
+
+        // A truncated buffer should fail, and the boxed error should carry
+        // the validation failure from rkyv.
+        let bytes = rkyv::to_bytes::<rancor::Error>(&stub).unwrap();
+        let mut truncated = AlignedVec::<16>::new();
+        truncated.extend_from_slice(&bytes[..bytes.len() - 1]);
+
+        let err =
+            OwnedArchive::<ArchiveStub, _>::new_boxed(truncated).unwrap_err();
+        assert!(err.to_string().contains("subtree pointer overran range"));
+    }
+
+    #[test]
+    fn test_owned_archive_byte_range_of() {
+        let stub = ArchiveStub { hello: 4, world: 5 };
+
+        let bytes = rkyv::to_bytes::<rancor::Error>(&stub).unwrap();
+        let mut owned: OwnedArchive<ArchiveStub, _> =
+            OwnedArchive::new::<rancor::Error>(bytes).unwrap();
+
+        let range = owned.byte_range_of(&owned.world);
+        assert_eq!(range.len(), 8);
+        assert!(range.end <= owned.as_bytes().len());
+
+        // Editing the field should only change the bytes in its range.
+        let before = owned.as_bytes().to_vec();
+        munge!(let ArchivedArchiveStub { mut world, ..} = owned.get_mut());
+        *world = u64::MAX.into();
+        let after = owned.as_bytes();
+
+        for (i, (a, b)) in before.iter().zip(after).enumerate() {
+            if !range.contains(&i) {
+                assert_eq!(a, b, "byte {i} outside the field changed");
+            }
+        }
+        assert!(after[range].iter().all(|&b| b == 0xff));
+    }
+
+    #[test]
+    #[should_panic(expected = "does not lie within")]
+    fn test_owned_archive_byte_range_of_foreign() {
+        let stub = ArchiveStub { hello: 4, world: 5 };
+
+        let bytes = rkyv::to_bytes::<rancor::Error>(&stub).unwrap();
+        let owned: OwnedArchive<ArchiveStub, _> =
+            OwnedArchive::new::<rancor::Error>(bytes).unwrap();
+
+        owned.byte_range_of(&stub.world);
+    }
+
+    #[test]
+    fn test_owned_archive_archive_as() {
+        // An external archived type that `Record` archives as.
+        #[derive(CheckBytes, Portable)]
+        #[bytecheck(crate = rkyv::bytecheck)]
+        #[rkyv(crate = rkyv)]
+        #[repr(C)]
+        struct RawRecord<T> {
+            id: T,
+            flag: bool,
+        }
+
+        #[derive(Archive, Serialize)]
+        #[rkyv(as = RawRecord<T::Archived>)]
+        struct Record<T> {
+            id: T,
+            flag: bool,
+        }
+
+        let record = Record {
+            id: 42u32,
+            flag: true,
+        };
+
+        let bytes = rkyv::to_bytes::<rancor::Error>(&record).unwrap();
+        let owned: OwnedArchive<Record<u32>, _> =
+            OwnedArchive::new::<rancor::Error>(bytes).unwrap();
+
+        let raw: &RawRecord<rkyv::Archived<u32>> = &owned;
+        assert_eq!(raw.id, 42);
+        assert!(raw.flag);
+    }
+
+    #[test]
+    fn test_owned_archive_misaligned() {
+        let stub = ArchiveStub { hello: 4, world: 5 };
+        let bytes = rkyv::to_bytes::<rancor::Error>(&stub).unwrap();
+        assert!(align_of::<ArchivedArchiveStub>() >= 8);
+
+        let err = OwnedArchive::<ArchiveStub, _>::new::<rancor::BoxedError>(
+            Misaligned::new(&bytes),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("unaligned"));
+    }
+
+    #[test]
+    fn test_owned_archive_misaligned_start() {
+        // Two leading bytes put the start of the buffer off alignment, but
+        // the root at the end of it stays aligned.
+        let mut aligned = AlignedVec::<16>::new();
+        aligned.extend_from_slice(&[0; 4]);
+        aligned.extend_from_slice(
+            &rkyv::to_bytes::<rancor::Error>(&7u32).unwrap(),
+        );
+        let bytes = &aligned[2..];
+        assert!(!bytes.as_ptr().cast::<u32>().is_aligned());
+
+        assert!(super::diagnose::<u32>(bytes).is_empty());
+        let owned =
+            OwnedArchive::<u32, _>::new::<rancor::Error>(bytes).unwrap();
+        assert_eq!(*owned, 7);
+        let Ok(owned) = OwnedArchive::<u32, _>::try_new::<rancor::Error>(bytes)
+        else {
+            panic!("an aligned root failed validation");
+        };
+        assert_eq!(*owned, 7);
+        let owned = OwnedArchive::<u32, _>::new_with_limits::<rancor::Error>(
+            bytes,
+            ValidationLimits::default(),
+        )
+        .unwrap();
+        assert_eq!(*owned, 7);
+
+        // Every constructor rejects a misaligned root the same way.
+        let misaligned = &aligned[3..7];
+        let Err((_, err)) =
+            OwnedArchive::<u32, _>::try_new::<rancor::BoxedError>(misaligned)
+        else {
+            panic!("a misaligned root passed validation");
+        };
+        assert!(err.to_string().contains("unaligned"));
+        let err =
+            OwnedArchive::<u32, _>::new_with_limits::<rancor::BoxedError>(
+                misaligned,
+                ValidationLimits::default(),
+            )
+            .unwrap_err();
+        assert!(err.to_string().contains("unaligned"));
+    }
+
+    #[test]
+    fn test_owned_archive_try_get_mut() {
+        #[derive(Archive, Serialize)]
+        pub struct Counter {
+            count: u8,
+        }
+
+        let bytes =
+            rkyv::to_bytes::<rancor::Error>(&Counter { count: 1 }).unwrap();
+
+        // Read-only containers cannot be mutated.
+        let mut borrowed: OwnedArchive<Counter, &[u8]> =
+            OwnedArchive::new::<rancor::Error>(bytes.as_slice()).unwrap();
+        assert!(borrowed.try_get_mut().is_none());
+
+        let mut owned: OwnedArchive<Counter, Vec<u8>> =
+            OwnedArchive::new::<rancor::Error>(bytes.to_vec()).unwrap();
+        let seal = owned.try_get_mut().unwrap();
+        munge!(let ArchivedCounter { mut count } = seal);
+        *count = 2;
+        assert_eq!(owned.count, 2);
+    }
+
+    #[test]
+    fn test_owned_archive_inplace_transform() {
+        const KEY: u8 = 0x5a;
+
+        let stub = ArchiveStub { hello: 4, world: 5 };
+
+        let mut bytes = rkyv::to_bytes::<rancor::Error>(&stub).unwrap();
+        bytes.iter_mut().for_each(|b| *b ^= KEY);
+
+        // Without the transform the scrambled bytes do not read back as the
+        // original value.
+        let scrambled: OwnedArchive<ArchiveStub, _> =
+            OwnedArchive::new::<rancor::Error>(bytes.clone()).unwrap();
+        assert_ne!(stub, *scrambled);
+
+        let owned: OwnedArchive<ArchiveStub, _> =
+            OwnedArchive::new_with_inplace_transform::<rancor::Error>(
+                bytes,
+                |bytes| bytes.iter_mut().for_each(|b| *b ^= KEY),
+            )
+            .unwrap();
+        assert_eq!(stub, *owned);
+    }
+
+    #[cfg(feature = "bytemuck")]
+    #[test]
+    fn test_owned_archive_new_pod() {
+        use bytemuck::AnyBitPattern;
+
+        #[derive(Archive, Serialize)]
+        #[rkyv(derive(Clone, Copy, Debug, PartialEq, AnyBitPattern))]
+        pub struct Flat {
+            id: [u8; 4],
+            level: u8,
+        }
+
+        let flat = Flat {
+            id: [1, 2, 3, 4],
+            level: 5,
+        };
+        let bytes = rkyv::to_bytes::<rancor::Error>(&flat).unwrap();
+
+        let pod: OwnedArchive<Flat, _> =
+            OwnedArchive::new_pod::<rancor::Error>(bytes.clone()).unwrap();
+        let checked: OwnedArchive<Flat, _> =
+            OwnedArchive::new::<rancor::Error>(bytes.clone()).unwrap();
+        assert_eq!(*pod, *checked);
+
+        // Anything but exactly one root is rejected.
+        let mut longer = bytes.clone();
+        longer.push(0);
+        assert!(
+            OwnedArchive::<Flat, _>::new_pod::<rancor::Error>(longer).is_err()
+        );
+        assert!(
+            OwnedArchive::<Flat, _>::new_pod::<rancor::Error>(&bytes[1..])
+                .is_err()
+        );
+    }
+
+    #[cfg(feature = "checksum")]
+    #[test]
+    fn test_owned_archive_checksum() {
+        let stub = ArchiveStub { hello: 4, world: 5 };
+
+        let first: OwnedArchive<ArchiveStub, _> =
+            OwnedArchive::new::<rancor::Error>(
+                rkyv::to_bytes::<rancor::Error>(&stub).unwrap(),
+            )
+            .unwrap();
+        let second: OwnedArchive<ArchiveStub, _> =
+            OwnedArchive::new::<rancor::Error>(
+                rkyv::to_bytes::<rancor::Error>(&stub).unwrap(),
+            )
+            .unwrap();
+        assert_eq!(first.checksum(), second.checksum());
+
+        let mut changed = rkyv::to_bytes::<rancor::Error>(&stub).unwrap();
+        changed[0] ^= 1;
+        let changed: OwnedArchive<ArchiveStub, _> =
+            OwnedArchive::new::<rancor::Error>(changed).unwrap();
+        assert_ne!(first.checksum(), changed.checksum());
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_owned_archive_par_chunked_checksum() {
+        let stubs = (0..1000)
+            .map(|i| ArchiveStub {
+                hello: (i % 256) as u8,
+                world: i,
+            })
+            .collect::<Vec<_>>();
+        let bytes = rkyv::to_bytes::<rancor::Error>(&stubs).unwrap();
+
+        // Chunk sizes that do and don't divide the buffer evenly.
+        for chunk_size in [1, 7, 64, 4096, bytes.len(), bytes.len() + 1] {
+            assert_eq!(
+                super::par_chunked_checksum(&bytes, chunk_size),
+                super::chunked_checksum(&bytes, chunk_size),
+            );
+        }
+
+        let expected = super::par_chunked_checksum(&bytes, 256);
+        let owned = OwnedArchive::<Vec<ArchiveStub>, _>::new_verified::<
+            rancor::Error,
+        >(bytes.clone(), 256, expected)
+        .unwrap();
+        assert_eq!(owned[999].world, 999);
+
+        let mut changed = bytes;
+        changed[0] ^= 1;
+        let Err(error) = OwnedArchive::<Vec<ArchiveStub>, _>::new_verified::<
+            rancor::BoxedError,
+        >(changed, 256, expected) else {
+            panic!("a corrupted buffer passed the checksum");
+        };
+        assert!(error.to_string().contains("does not match"));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_owned_archive_serialize_bytes_with() {
+        let stub = ArchiveStub { hello: 4, world: 5 };
+
+        let bytes = rkyv::to_bytes::<rancor::Error>(&stub).unwrap();
+        let owned: OwnedArchive<ArchiveStub, _> =
+            OwnedArchive::new::<rancor::Error>(bytes).unwrap();
+
+        let mut encoded = Vec::new();
+        owned
+            .serialize_bytes_with(&mut bincode::Serializer::new(
+                &mut encoded,
+                bincode::DefaultOptions::new(),
+            ))
+            .unwrap();
+
+        // The bytes come back as a plain byte sequence.
+        let decoded: Vec<u8> = bincode::Options::deserialize(
+            bincode::DefaultOptions::new(),
+            &encoded,
+        )
+        .unwrap();
+        assert_eq!(decoded, owned.as_bytes());
+
+        let mut aligned = AlignedVec::<16>::new();
+        aligned.extend_from_slice(&decoded);
+        let restored: OwnedArchive<ArchiveStub, _> =
+            OwnedArchive::new::<rancor::Error>(aligned).unwrap();
+        assert_eq!(stub, *restored);
+    }
+
+    #[test]
+    fn test_owned_archive_new_and_deserialize() {
+        let stub = ArchiveStub { hello: 4, world: 5 };
+
+        let bytes = rkyv::to_bytes::<rancor::Error>(&stub).unwrap();
+        let (owned, value) =
+            OwnedArchive::<ArchiveStub, _>::new_and_deserialize::<
+                rancor::Error,
+            >(bytes)
+            .unwrap();
+
+        assert_eq!(value, stub);
+        assert_eq!(stub, *owned);
+    }
+
+    #[test]
+    fn test_owned_archive_shared_pointers() {
+        #[derive(Archive, Serialize)]
+        pub struct SharedStub {
+            first: Rc<ArchiveStub>,
+            second: Rc<ArchiveStub>,
+        }
+
+        let inner = Rc::new(ArchiveStub { hello: 4, world: 5 });
+        let shared = SharedStub {
+            first: inner.clone(),
+            second: inner,
+        };
+
+        let bytes = rkyv::to_bytes::<rancor::Error>(&shared).unwrap();
+        let owned: OwnedArchive<SharedStub, _> =
+            OwnedArchive::new::<rancor::Error>(bytes).unwrap();
+
+        // Both pointers should have been deduplicated to one archived value.
+        let first: &ArchivedArchiveStub = &owned.first;
+        let second: &ArchivedArchiveStub = &owned.second;
+        assert_eq!(owned.byte_range_of(first), owned.byte_range_of(second));
+        assert_eq!(first.hello, 4);
+        assert_eq!(second.world, 5);
+    }
+
+    #[test]
+    fn test_owned_archive_is_aligned() {
+        let stub = ArchiveStub { hello: 4, world: 5 };
+
+        let bytes = rkyv::to_bytes::<rancor::Error>(&stub).unwrap();
+        let misaligned = Misaligned::new(&bytes);
+
+        let owned: OwnedArchive<ArchiveStub, _> =
+            OwnedArchive::new::<rancor::Error>(bytes).unwrap();
+        assert!(owned.is_aligned());
+        owned.assert_aligned();
+
+        // A misaligned archive can't pass validation, so build one unchecked.
+        // It is never dereferenced.
+        let owned: OwnedArchive<ArchiveStub, _> =
+            unsafe { OwnedArchive::new_unchecked(misaligned) };
+        assert!(!owned.is_aligned());
+
+        // Only the root has to be aligned, not the start of the buffer.
+        let mut aligned = AlignedVec::<16>::new();
+        aligned.extend_from_slice(&[0; 4]);
+        aligned.extend_from_slice(
+            &rkyv::to_bytes::<rancor::Error>(&7u32).unwrap(),
+        );
+        let owned = OwnedArchive::<u32, _>::new::<rancor::Error>(&aligned[2..])
+            .unwrap();
+        assert!(owned.is_aligned());
+        owned.assert_aligned();
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "is not aligned")]
+    fn test_owned_archive_assert_aligned() {
+        let stub = ArchiveStub { hello: 4, world: 5 };
+
+        let bytes = rkyv::to_bytes::<rancor::Error>(&stub).unwrap();
+        let owned: OwnedArchive<ArchiveStub, _> =
+            unsafe { OwnedArchive::new_unchecked(Misaligned::new(&bytes)) };
+        owned.assert_aligned();
+    }
+
+    #[test]
+    fn test_owned_archive_depth_limit() {
+        #[derive(Archive, Serialize)]
+        #[rkyv(
+            bytecheck(bounds(__C: rkyv::validation::ArchiveContext)),
+            serialize_bounds(__S: rkyv::ser::Writer),
+        )]
+        pub enum Node {
+            Nil,
+            Cons(#[rkyv(omit_bounds)] Box<Node>),
+        }
+
+        let mut node = Node::Nil;
+        for _ in 0..1000 {
+            node = Node::Cons(Box::new(node));
+        }
+        let bytes = rkyv::to_bytes::<rancor::Error>(&node).unwrap();
+
+        let limits = ValidationLimits {
+            max_depth: NonZeroUsize::new(16),
+        };
+        let result = OwnedArchive::<Node, _>::new_with_limits::<
+            rancor::BoxedError,
+        >(bytes.clone(), limits);
+        let Err(err) = result else {
+            panic!("an archive deeper than the limit passed validation");
+        };
+        assert!(err.to_string().contains("maximum subtree depth"));
+
+        // The same archive is fine with a high enough limit.
+        let limits = ValidationLimits {
+            max_depth: NonZeroUsize::new(2000),
+        };
+        OwnedArchive::<Node, _>::new_with_limits::<rancor::Error>(
+            bytes, limits,
+        )
+        .unwrap();
+
+        // Avoid recursing through 1000 boxes when dropping.
+        while let Node::Cons(next) = node {
+            node = *next;
+        }
+    }
+
+    #[test]
+    fn test_owned_archive_byte_array() {
+        let mut hash = [0u8; 32];
+        for (i, b) in hash.iter_mut().enumerate() {
+            *b = i as u8;
+        }
+
+        let bytes = rkyv::to_bytes::<rancor::Error>(&hash).unwrap();
+        let owned: OwnedArchive<[u8; 32], _> =
+            OwnedArchive::new::<rancor::Error>(bytes).unwrap();
+
+        let archived: &[u8; 32] = owned.as_archived_slice();
+        assert_eq!(archived, &hash);
+    }
+
+    #[test]
+    fn test_owned_archive_assume_valid_then() {
+        let stub = ArchiveStub { hello: 4, world: 5 };
+
+        let bytes = rkyv::to_bytes::<rancor::Error>(&stub).unwrap();
+        let checked: OwnedArchive<ArchiveStub, _> =
+            OwnedArchive::new::<rancor::Error>(bytes.clone()).unwrap();
+
+        let (unchecked, world) = unsafe {
+            OwnedArchive::<ArchiveStub, _>::assume_valid_then(bytes, |a| {
+                a.world.to_native()
+            })
+        };
+
+        assert_eq!(world, checked.world);
+        assert_eq!(stub, *unchecked);
+        assert_eq!(unchecked.as_bytes(), checked.as_bytes());
+    }
+
+    #[test]
+    fn test_owned_archive_rewrap() {
+        #[derive(Archive, Serialize)]
+        pub struct Meters(u32);
+
+        #[derive(Archive, Serialize)]
+        pub struct Distance(Meters);
+
+        let bytes =
+            rkyv::to_bytes::<rancor::Error>(&Distance(Meters(7))).unwrap();
+        let distance: OwnedArchive<Distance, _> =
+            OwnedArchive::new::<rancor::Error>(bytes).unwrap();
+
+        // SAFETY: `ArchivedDistance` is a `#[repr(C)]` struct whose only
+        // field is an `ArchivedMeters`, so the two share a layout.
+        let meters: OwnedArchive<Meters, _> = unsafe { distance.rewrap() };
+        assert_eq!(meters.0, 7);
+    }
+
+    #[test]
+    fn test_owned_archive_iter_mut() {
+        let stubs = (0..10)
+            .map(|i| ArchiveStub { hello: i, world: 0 })
+            .collect::<Vec<_>>();
+        let bytes = rkyv::to_bytes::<rancor::Error>(&stubs).unwrap();
+        let mut owned: OwnedArchive<Vec<ArchiveStub>, _> =
+            OwnedArchive::new::<rancor::Error>(bytes).unwrap();
+
+        let elements = owned.iter_mut();
+        assert_eq!(elements.len(), 10);
+        for stub in elements {
+            munge!(let ArchivedArchiveStub { hello, mut world } = stub);
+            *world = (u64::from(*hello) * 2).into();
+        }
+
+        for (i, stub) in owned.iter().enumerate() {
+            assert_eq!(stub.hello, i as u8);
+            assert_eq!(stub.world, i as u64 * 2);
+        }
+    }
+
+    #[test]
+    fn test_owned_archive_clone_into_container() {
+        let stub = ArchiveStub { hello: 4, world: 5 };
+        let bytes = rkyv::to_bytes::<rancor::Error>(&stub).unwrap();
+        let shared: Rc<[u8]> = Rc::from(bytes.as_slice());
+        let owned: OwnedArchive<ArchiveStub, _> =
+            OwnedArchive::new::<rancor::Error>(shared).unwrap();
+
+        let copy = owned
+            .clone_into_container::<AlignedVec, rancor::Error>()
+            .unwrap();
+        assert_eq!(stub, *copy);
+        assert_eq!(copy.as_bytes(), owned.as_bytes());
+        assert_ne!(copy.as_bytes().as_ptr(), owned.as_bytes().as_ptr());
+        assert_eq!(stub, *owned);
+    }
+
+    #[test]
+    fn test_owned_archive_freeze() {
+        fn assert_send_sync<T: Send + Sync>(_: &T) {}
+
+        let stub = ArchiveStub { hello: 4, world: 5 };
+        let bytes = rkyv::to_bytes::<rancor::Error>(&stub).unwrap();
+        let mut owned: OwnedArchive<ArchiveStub, _> =
+            OwnedArchive::new::<rancor::Error>(bytes).unwrap();
+        munge!(let ArchivedArchiveStub { mut world, .. } = owned.get_mut());
+        *world = 6.into();
+
+        let ptr = owned.as_bytes().as_ptr();
+
+        let frozen = owned.freeze();
+        assert_send_sync(&frozen);
+        assert_eq!(frozen.hello, 4);
+        assert_eq!(frozen.world, 6);
+        // The bytes were moved, not copied.
+        assert_eq!(frozen.as_bytes().as_ptr(), ptr);
+
+        let shared = frozen.clone();
+        let handle = std::thread::spawn(move || shared.world.to_native());
+        assert_eq!(handle.join().unwrap(), 6);
+        assert_eq!(frozen.world, 6);
+
+        // Over-aligned contents survive, since the buffer keeps its
+        // alignment.
+        #[derive(Archive, Serialize)]
+        pub struct Wide {
+            value: u128,
+        }
+        let bytes =
+            rkyv::to_bytes::<rancor::Error>(&Wide { value: 7 }).unwrap();
+        let owned =
+            OwnedArchive::<Wide, _>::new::<rancor::Error>(bytes).unwrap();
+        assert_eq!(owned.freeze().value, 7);
+    }
+
+    #[test]
+    fn test_owned_archive_new_reported() {
+        let bytes = rkyv::to_bytes::<rancor::Error>(&vec![1u32, 2, 3]).unwrap();
+        let (owned, report) =
+            OwnedArchive::<Vec<u32>, _>::new_reported::<rancor::Error>(bytes)
+                .unwrap();
+
+        // The three elements come first, followed by the vector itself.
+        assert_eq!(
+            report,
+            ValidationReport {
+                total_len: 20,
+                archived_size: 8,
+                root_pos: 12,
+            }
+        );
+        assert_eq!(owned.as_bytes().len(), report.total_len);
+    }
+
+    #[test]
+    fn test_owned_archive_explicit_endianness() {
+        use rkyv::rend::{u16_be, u16_le, u32_be, u32_le};
+
+        #[derive(Archive, Serialize)]
+        pub struct Header {
+            big: u32_be,
+            little: u32_le,
+        }
+
+        // Fixtures written byte by byte, independent of the host.
+        let mut be = AlignedVec::<16>::new();
+        be.extend_from_slice(&[0x12, 0x34]);
+        let owned: OwnedArchive<u16_be, _> =
+            OwnedArchive::new::<rancor::Error>(be).unwrap();
+        assert_eq!(owned.to_native(), 0x1234);
+
+        let mut le = AlignedVec::<16>::new();
+        le.extend_from_slice(&[0x12, 0x34]);
+        let owned: OwnedArchive<u16_le, _> =
+            OwnedArchive::new::<rancor::Error>(le).unwrap();
+        assert_eq!(owned.to_native(), 0x3412);
+
+        // Mixed endianness within one serialized archive.
+        let header = Header {
+            big: u32_be::from_native(0xdead_beef),
+            little: u32_le::from_native(0xdead_beef),
+        };
+        let bytes = rkyv::to_bytes::<rancor::Error>(&header).unwrap();
+        assert_eq!(bytes[..4], [0xde, 0xad, 0xbe, 0xef]);
+        assert_eq!(bytes[4..], [0xef, 0xbe, 0xad, 0xde]);
+
+        let owned: OwnedArchive<Header, _> =
+            OwnedArchive::new::<rancor::Error>(bytes).unwrap();
+        assert_eq!(owned.big.to_native(), 0xdead_beef);
+        assert_eq!(owned.little.to_native(), 0xdead_beef);
+    }
+
+    #[test]
+    fn test_owned_archive_raw_parts() {
+        let stub = ArchiveStub { hello: 4, world: 5 };
+        let bytes = rkyv::to_bytes::<rancor::Error>(&stub).unwrap();
+        let owned: OwnedArchive<ArchiveStub, _> =
+            OwnedArchive::new::<rancor::Error>(bytes).unwrap();
+
+        let bytes =
+            unsafe { std::slice::from_raw_parts(owned.as_ptr(), owned.len()) };
+        assert_eq!(bytes, owned.as_bytes());
+        assert!(!owned.is_empty());
+
+        // The root sits at the end of the buffer.
+        let root = owned.archived_ptr();
+        assert_eq!(root.cast::<u8>(), unsafe {
+            owned
+                .as_ptr()
+                .add(owned.len() - core::mem::size_of::<ArchivedArchiveStub>())
+        });
+        assert_eq!(unsafe { (*root).world }, 5);
+    }
+
+    #[test]
+    fn test_owned_archive_from_serializer() {
+        use rkyv::{
+            api::serialize_using,
+            ser::{sharing::Share, Serializer},
+            util::with_arena,
+        };
+
+        let stub = ArchiveStub { hello: 4, world: 5 };
+        let owned = with_arena(|arena| {
+            let mut serializer = Serializer::new(
+                AlignedVec::<16>::new(),
+                arena.acquire(),
+                Share::new(),
+            );
+            serialize_using::<_, rancor::Error>(&stub, &mut serializer)
+                .unwrap();
+            let ptr = serializer.writer.as_ptr();
+
+            let owned = OwnedArchive::<ArchiveStub, _>::from_serializer::<
+                _,
+                _,
+                rancor::Error,
+            >(serializer)
+            .unwrap();
+            // The serializer's buffer was reused rather than copied.
+            assert_eq!(owned.as_ptr(), ptr);
+            owned
+        });
+        assert_eq!(stub, *owned);
+    }
+
+    #[test]
+    fn test_owned_archive_split_at() {
+        let values = (0..10u32).collect::<Vec<_>>();
+        let bytes = rkyv::to_bytes::<rancor::Error>(&values).unwrap();
+        let owned: OwnedArchive<Vec<u32>, _> =
+            OwnedArchive::new::<rancor::Error>(bytes).unwrap();
+
+        let (left, right) = owned.split_at(4);
+        assert_eq!(left, [0, 1, 2, 3]);
+        assert_eq!(right, [4, 5, 6, 7, 8, 9]);
+
+        let (all, none) = owned.split_at(10);
+        assert_eq!(all.len(), 10);
+        assert!(none.is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "mid > len")]
+    fn test_owned_archive_split_at_out_of_bounds() {
+        let bytes = rkyv::to_bytes::<rancor::Error>(&vec![1u32]).unwrap();
+        let owned: OwnedArchive<Vec<u32>, _> =
+            OwnedArchive::new::<rancor::Error>(bytes).unwrap();
+        let _ = owned.split_at(2);
+    }
+
+    #[test]
+    fn test_owned_archive_try_new() {
+        #[derive(Archive, Serialize)]
+        pub struct Flag {
+            set: bool,
+        }
+
+        let mut bytes =
+            rkyv::to_bytes::<rancor::Error>(&Flag { set: true }).unwrap();
+        bytes[0] = 0xff;
+        let input = bytes.to_vec();
+
+        let Err((bytes, error)) =
+            OwnedArchive::<Flag, _>::try_new::<rancor::Error>(bytes)
+        else {
+            panic!("an invalid bool passed validation");
+        };
+        assert_eq!(bytes.as_slice(), input);
+        assert!(!error.to_string().is_empty());
+    }
+
+    #[test]
+    fn test_owned_archive_equals_bytes() {
+        let stub = ArchiveStub { hello: 4, world: 5 };
+        let bytes = rkyv::to_bytes::<rancor::Error>(&stub).unwrap();
+        let owned: OwnedArchive<ArchiveStub, _> =
+            OwnedArchive::new::<rancor::Error>(bytes.clone()).unwrap();
+
+        assert!(owned.equals_bytes(&bytes));
+
+        let mut changed = bytes.to_vec();
+        changed[0] ^= 1;
+        assert!(!owned.equals_bytes(&changed));
+        assert!(!owned.equals_bytes(&bytes[1..]));
+    }
+
+    #[test]
+    fn test_owned_archive_try_map_container() {
+        use rkyv::rancor::Source;
+
+        let stub = ArchiveStub { hello: 4, world: 5 };
+        let bytes = rkyv::to_bytes::<rancor::Error>(&stub).unwrap();
+        let owned: OwnedArchive<ArchiveStub, _> =
+            OwnedArchive::new::<rancor::Error>(bytes).unwrap();
+
+        let boxed = owned
+            .clone()
+            .try_map_container::<Box<[u8]>, rancor::Error>(|bytes| {
+                Ok(bytes.as_slice().into())
+            })
+            .unwrap();
+        assert_eq!(stub, *boxed);
+
+        let failed =
+            owned.try_map_container::<Box<[u8]>, rancor::BoxedError>(|_| {
+                Err(rancor::BoxedError::new(std::io::Error::other("no memory")))
+            });
+        match failed {
+            Ok(_) => panic!("a failing conversion succeeded"),
+            Err(error) => assert!(error.to_string().contains("no memory")),
+        }
+    }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn test_owned_archive_tracing() {
+        use std::sync::{
+            atomic::{AtomicU64, AtomicUsize, Ordering},
+            Arc,
+        };
+
+        use tracing::{
+            span::{Attributes, Id, Record},
+            Event, Level, Metadata, Subscriber,
+        };
+
+        #[derive(Default)]
+        struct CountErrors {
+            next_id: AtomicU64,
+            errors: Arc<AtomicUsize>,
+        }
+
+        impl Subscriber for CountErrors {
+            fn enabled(&self, _: &Metadata<'_>) -> bool {
+                true
+            }
+
+            fn new_span(&self, _: &Attributes<'_>) -> Id {
+                Id::from_u64(self.next_id.fetch_add(1, Ordering::Relaxed) + 1)
+            }
+
+            fn record(&self, _: &Id, _: &Record<'_>) {}
+
+            fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+            fn event(&self, event: &Event<'_>) {
+                if *event.metadata().level() == Level::ERROR {
+                    self.errors.fetch_add(1, Ordering::Relaxed);
+                }
+            }
+
+            fn enter(&self, _: &Id) {}
+
+            fn exit(&self, _: &Id) {}
+        }
+
+        #[derive(Archive, Serialize)]
+        pub struct Flag {
+            set: bool,
+        }
+
+        let subscriber = CountErrors::default();
+        let errors = subscriber.errors.clone();
+        tracing::subscriber::with_default(subscriber, || {
+            let bytes =
+                rkyv::to_bytes::<rancor::Error>(&Flag { set: true }).unwrap();
+            assert!(
+                OwnedArchive::<Flag, _>::new::<rancor::Error>(bytes).is_ok()
+            );
+            assert_eq!(errors.load(Ordering::Relaxed), 0);
+
+            let mut bytes =
+                rkyv::to_bytes::<rancor::Error>(&Flag { set: true }).unwrap();
+            bytes[0] = 2;
+            assert!(
+                OwnedArchive::<Flag, _>::new::<rancor::Error>(bytes).is_err()
+            );
+            assert_eq!(errors.load(Ordering::Relaxed), 1);
+        });
+    }
+
+    #[test]
+    fn test_owned_archive_compact() {
+        let stub = ArchiveStub { hello: 4, world: 5 };
+        let serialized = rkyv::to_bytes::<rancor::Error>(&stub).unwrap();
+
+        let mut bytes = AlignedVec::<16>::with_capacity(4096);
+        bytes.extend_from_slice(&serialized);
+        let owned: OwnedArchive<ArchiveStub, _> =
+            OwnedArchive::new::<rancor::Error>(bytes).unwrap();
+        assert!(owned.container().capacity() >= 4096);
+
+        let compacted = owned.compact::<rancor::Error, 16>().unwrap();
+        assert_eq!(compacted.len(), serialized.len());
+        assert_eq!(compacted.container().capacity(), serialized.len());
+        assert_eq!(stub, *compacted);
+
+        #[derive(Archive, Serialize)]
+        #[rkyv(attr(repr(align(32))))]
+        pub struct Wide {
+            value: u8,
+        }
 
-// ==============
-// Implementations of `StableBytes` for popular types
-// ==============
+        // Archives aligned beyond 16 bytes keep their alignment.
+        let mut bytes = AlignedVec::<32>::with_capacity(4096);
+        bytes.extend_from_slice(
+            &rkyv::to_bytes::<rancor::Error>(&Wide { value: 7 }).unwrap(),
+        );
+        let owned =
+            OwnedArchive::<Wide, _>::new::<rancor::Error>(bytes).unwrap();
+        let compacted = owned.compact::<rancor::Error, 32>().unwrap();
+        assert_eq!(compacted.value, 7);
+        assert_eq!(compacted.as_ptr() as usize % 32, 0);
+        assert_eq!(compacted.container().capacity(), compacted.len());
+    }
 
-unsafe impl StableBytesMut for AlignedVec {
-    fn bytes_mut(&mut self) -> &mut [u8] {
-        self.as_mut()
+    #[test]
+    fn test_owned_archive_deserialize_field() {
+        let stub = ArchiveStub { hello: 4, world: 5 };
+        let bytes = rkyv::to_bytes::<rancor::Error>(&stub).unwrap();
+        let owned: OwnedArchive<ArchiveStub, _> =
+            OwnedArchive::new::<rancor::Error>(bytes).unwrap();
+
+        let world = owned
+            .deserialize_field::<u64, rancor::Error>(|stub| &stub.world)
+            .unwrap();
+        assert_eq!(world, 5);
     }
-}
 
-unsafe impl StableBytes for AlignedVec {
-    fn bytes(&self) -> &[u8] {
-        self.as_ref()
+    #[test]
+    fn test_owned_archive_deserialize_nested_field() {
+        #[derive(Archive, Serialize)]
+        struct Outer {
+            id: u32,
+            inner: ArchiveStub,
+        }
+
+        let outer = Outer {
+            id: 1,
+            inner: ArchiveStub { hello: 4, world: 5 },
+        };
+        let bytes = rkyv::to_bytes::<rancor::Error>(&outer).unwrap();
+        let owned: OwnedArchive<Outer, _> =
+            OwnedArchive::new::<rancor::Error>(bytes).unwrap();
+
+        let inner = owned
+            .deserialize_field::<ArchiveStub, rancor::Error>(|outer| {
+                &outer.inner
+            })
+            .unwrap();
+        assert_eq!(inner, outer.inner);
+        assert_eq!(owned.id, 1);
     }
-}
 
-unsafe impl StableBytesMut for Vec<u8> {
-    fn bytes_mut(&mut self) -> &mut [u8] {
-        self.as_mut()
+    #[test]
+    fn test_owned_archive_map_or() {
+        #[derive(Archive, Serialize)]
+        struct Settings {
+            limit: Option<ArchiveStub>,
+        }
+
+        let default = ArchivedArchiveStub {
+            hello: 0,
+            world: 0.into(),
+        };
+
+        let present = Settings {
+            limit: Some(ArchiveStub { hello: 4, world: 5 }),
+        };
+        let bytes = rkyv::to_bytes::<rancor::Error>(&present).unwrap();
+        let owned: OwnedArchive<Settings, _> =
+            OwnedArchive::new::<rancor::Error>(bytes).unwrap();
+        let limit = owned.map_or::<ArchiveStub>(&default, |settings| {
+            settings.limit.as_ref()
+        });
+        assert_eq!(*limit, ArchiveStub { hello: 4, world: 5 });
+
+        let absent = Settings { limit: None };
+        let bytes = rkyv::to_bytes::<rancor::Error>(&absent).unwrap();
+        let owned: OwnedArchive<Settings, _> =
+            OwnedArchive::new::<rancor::Error>(bytes).unwrap();
+        let limit = owned.map_or::<ArchiveStub>(&default, |settings| {
+            settings.limit.as_ref()
+        });
+        assert!(core::ptr::eq(limit, &default));
     }
-}
 
-unsafe impl StableBytes for Vec<u8> {
-    fn bytes(&self) -> &[u8] {
-        self.as_ref()
+    #[test]
+    fn test_borrowed_mut_archive() {
+        let stub = ArchiveStub { hello: 4, world: 5 };
+        let mut scratch =
+            rkyv::to_bytes::<rancor::Error>(&stub).unwrap().to_vec();
+
+        {
+            let mut archive = BorrowedMutArchive::<ArchiveStub>::new::<
+                rancor::Error,
+            >(&mut scratch)
+            .unwrap();
+            let seal = archive.get_mut();
+            munge!(let ArchivedArchiveStub { mut world, .. } = seal);
+            *world = 6.into();
+            assert_eq!(archive.world, 6);
+        }
+
+        // The edits persist in the `Vec` once the borrow ends, and the `Vec`
+        // can be reused.
+        let owned: OwnedArchive<ArchiveStub, _> =
+            OwnedArchive::new::<rancor::Error>(scratch.clone()).unwrap();
+        assert_eq!(owned.world, 6);
+        scratch.clear();
+        assert!(scratch.is_empty());
     }
-}
 
-unsafe impl StableBytes for Arc<[u8]> {
-    fn bytes(&self) -> &[u8] {
-        self.as_ref()
+    #[test]
+    fn test_owned_archive_verify_layout_compat() {
+        #[derive(Archive, Serialize)]
+        pub struct Code {
+            value: u8,
+        }
+
+        #[derive(Archive, Serialize)]
+        pub struct Byte {
+            value: u8,
+        }
+
+        #[derive(Archive, Serialize)]
+        pub struct Flag {
+            set: bool,
+        }
+
+        let bytes =
+            rkyv::to_bytes::<rancor::Error>(&Code { value: 7 }).unwrap();
+        let owned: OwnedArchive<Code, _> =
+            OwnedArchive::new::<rancor::Error>(bytes).unwrap();
+
+        assert!(owned.verify_layout_compat::<Byte, rancor::Error>().is_ok());
+        // 7 is not a valid `bool`.
+        assert!(owned.verify_layout_compat::<Flag, rancor::Error>().is_err());
     }
-}
 
-unsafe impl StableBytes for Rc<[u8]> {
-    fn bytes(&self) -> &[u8] {
-        self.as_ref()
+    #[test]
+    fn test_owned_archive_as_slice_of() {
+        #[derive(Archive, Serialize)]
+        pub struct Record {
+            id: u32,
+            score: u32,
+        }
+
+        let records = [
+            Record { id: 1, score: 10 },
+            Record { id: 2, score: 20 },
+            Record { id: 3, score: 30 },
+        ];
+        let bytes = rkyv::to_bytes::<rancor::Error>(&records).unwrap();
+        let owned: OwnedArchive<[Record; 3], _> =
+            OwnedArchive::new::<rancor::Error>(bytes).unwrap();
+
+        let slice = owned.as_slice_of::<Record, rancor::Error>().unwrap();
+        assert_eq!(slice.len(), 3);
+        assert_eq!(slice[1].id, 2);
+        assert_eq!(slice[2].score, 30);
+
+        // 24 bytes are not a whole number of 16-byte stubs.
+        assert!(owned.as_slice_of::<ArchiveStub, rancor::Error>().is_err());
     }
-}
 
-unsafe impl StableBytesMut for Box<[u8]> {
-    fn bytes_mut(&mut self) -> &mut [u8] {
-        self.as_mut()
+    #[test]
+    fn test_owned_archive_hash() {
+        use core::hash::{Hash, Hasher};
+
+        /// 64-bit FNV-1a, which is deterministic across runs and hosts.
+        struct Fnv(u64);
+
+        impl Hasher for Fnv {
+            fn finish(&self) -> u64 {
+                self.0
+            }
+
+            fn write(&mut self, bytes: &[u8]) {
+                for byte in bytes {
+                    self.0 ^= u64::from(*byte);
+                    self.0 = self.0.wrapping_mul(0x100_0000_01b3);
+                }
+            }
+        }
+
+        fn fnv(value: &impl Hash) -> u64 {
+            let mut hasher = Fnv(0xcbf2_9ce4_8422_2325);
+            value.hash(&mut hasher);
+            hasher.finish()
+        }
+
+        let bytes = rkyv::to_bytes::<rancor::Error>(&"hello world".to_string())
+            .unwrap();
+        let owned: OwnedArchive<String, _> =
+            OwnedArchive::new::<rancor::Error>(bytes).unwrap();
+
+        assert_eq!(fnv(&owned), fnv(&owned.clone()));
+        assert_eq!(fnv(&owned), fnv(&"hello world"));
+        // A regression fixture: this must never change for the same input.
+        assert_eq!(fnv(&owned), 0x782d_3f88_cd58_fec8);
     }
-}
 
-unsafe impl StableBytes for Box<[u8]> {
-    fn bytes(&self) -> &[u8] {
-        self.as_ref()
+    #[test]
+    fn test_owned_archive_reborrow() {
+        fn assert_copy<T: Copy>(_: &T) {}
+
+        let stub = ArchiveStub { hello: 4, world: 5 };
+        let bytes = rkyv::to_bytes::<rancor::Error>(&stub).unwrap();
+        let owned: OwnedArchive<ArchiveStub, _> =
+            OwnedArchive::new::<rancor::Error>(bytes).unwrap();
+
+        let view: BorrowedArchive<'_, ArchiveStub> = owned.reborrow();
+        assert_copy(&view);
+        let copy = view;
+        assert_eq!(stub, *view);
+        assert_eq!(stub, *copy);
+        assert_eq!(view.as_ptr(), owned.as_ptr());
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use rkyv::{munge::munge, rancor, Archive, Deserialize, Serialize};
+    #[test]
+    fn test_owned_archive_map_all() {
+        let stubs = (0..5)
+            .map(|i| ArchiveStub {
+                hello: i,
+                world: u64::from(i) * 100,
+            })
+            .collect::<Vec<_>>();
+        let bytes = rkyv::to_bytes::<rancor::Error>(&stubs).unwrap();
+        let owned: OwnedArchive<Vec<ArchiveStub>, _> =
+            OwnedArchive::new::<rancor::Error>(bytes).unwrap();
 
-    use super::OwnedArchive;
+        let worlds: Vec<u64> = owned.map_all(|stub| stub.world.to_native());
+        assert_eq!(worlds, [0, 100, 200, 300, 400]);
+    }
 
-    #[derive(Archive, Clone, PartialEq, Deserialize, Serialize, Debug)]
-    #[rkyv(compare(PartialEq), derive(Debug))]
-    pub struct ArchiveStub {
-        hello: u8,
-        world: u64,
+    #[test]
+    fn test_owned_archive_scan() {
+        let stubs = (0..5)
+            .map(|i| ArchiveStub {
+                hello: i,
+                world: u64::from(i) * 100,
+            })
+            .collect::<Vec<_>>();
+        let bytes = rkyv::to_bytes::<rancor::Error>(&stubs).unwrap();
+        let owned: OwnedArchive<Vec<ArchiveStub>, _> =
+            OwnedArchive::new::<rancor::Error>(bytes).unwrap();
+
+        let hellos: Vec<u8> = owned
+            .scan(|stub| stub.world >= 200)
+            .map(|stub| stub.hello)
+            .collect();
+        assert_eq!(hellos, [2, 3, 4]);
+        assert_eq!(owned.scan(|stub| stub.hello > 4).count(), 0);
     }
 
     #[test]
-    fn test_owned_archive_vec() {
+    fn test_owned_archive_byte_view() {
+        let stub = ArchiveStub { hello: 4, world: 5 };
+        let bytes = rkyv::to_bytes::<rancor::Error>(&stub).unwrap();
+        let owned: OwnedArchive<ArchiveStub, _> =
+            OwnedArchive::new::<rancor::Error>(bytes).unwrap();
+
+        let range = owned.byte_range_of(&owned.world);
+        assert_eq!(owned.byte_view(range), Some(&5u64.to_le_bytes()[..]),);
+        assert_eq!(owned.byte_view(0..owned.len()), Some(owned.as_bytes()));
+        assert_eq!(owned.byte_view(0..owned.len() + 1), None);
+        assert_eq!(owned.byte_view(owned.len() + 1..owned.len() + 2), None);
+    }
+
+    #[test]
+    fn test_owned_archive_parts() {
+        let stub = ArchiveStub { hello: 4, world: 5 };
+        let bytes =
+            rkyv::to_bytes::<rancor::Error>(&vec![stub.clone()]).unwrap();
+        let owned: OwnedArchive<Vec<ArchiveStub>, _> =
+            OwnedArchive::new::<rancor::Error>(bytes).unwrap();
+
+        let (bytes, root_pos) = owned.into_parts();
+        // One stub, followed by the vector at the end.
+        assert_eq!(root_pos, 16);
+        assert_eq!(bytes.len(), 24);
+
+        let owned = unsafe {
+            OwnedArchive::<Vec<ArchiveStub>, _>::from_parts(bytes, root_pos)
+        };
+        assert_eq!(owned[0], stub);
+    }
+
+    #[test]
+    fn test_owned_archive_as_str() {
+        // Long enough to be stored out of line rather than inline.
+        let text = "the quick brown fox jumps over the lazy dog".to_string();
+        let bytes = rkyv::to_bytes::<rancor::Error>(&text).unwrap();
+        let owned: OwnedArchive<String, _> =
+            OwnedArchive::new::<rancor::Error>(bytes).unwrap();
+        assert_eq!(owned.as_str(), text);
+
+        let mut bytes = rkyv::to_bytes::<rancor::Error>(&text).unwrap();
+        bytes[0] = 0xff;
+        let result = OwnedArchive::<String, _>::new::<rancor::Error>(bytes);
+        assert!(result.is_err(), "invalid UTF-8 passed validation");
+    }
+
+    #[test]
+    fn test_owned_archive_get_key() {
+        use std::collections::HashMap;
+
+        let map = (0..4)
+            .map(|i| (format!("key {i}"), ArchiveStub { hello: i, world: 0 }))
+            .collect::<HashMap<_, _>>();
+        let bytes = rkyv::to_bytes::<rancor::Error>(&map).unwrap();
+        let owned: OwnedArchive<HashMap<String, ArchiveStub>, _> =
+            OwnedArchive::new::<rancor::Error>(bytes).unwrap();
+
+        assert_eq!(owned.get_key("key 2").map(|stub| stub.hello), Some(2));
+        assert!(owned.get_key("key 4").is_none());
+    }
+
+    #[test]
+    fn test_owned_archive_deserialize_with_pool() {
+        #[derive(Archive, Deserialize, Serialize)]
+        pub struct Shared {
+            name: Rc<String>,
+            stub: ArchiveStub,
+        }
+
+        let shared = Shared {
+            name: Rc::new("shared".to_string()),
+            stub: ArchiveStub { hello: 4, world: 5 },
+        };
+        let bytes = rkyv::to_bytes::<rancor::Error>(&shared).unwrap();
+        let owned: OwnedArchive<Shared, _> =
+            OwnedArchive::new::<rancor::Error>(bytes).unwrap();
+
+        let mut pool = rkyv::de::Pool::new();
+        let first = owned
+            .deserialize_with_pool::<rancor::Error>(&mut pool)
+            .unwrap();
+        let second = owned
+            .deserialize_with_pool::<rancor::Error>(&mut pool)
+            .unwrap();
+        let fresh = owned.deserialize::<rancor::Error>().unwrap();
+
+        assert_eq!(first.name, fresh.name);
+        assert_eq!(first.stub, fresh.stub);
+        // The pool hands out the same `Rc` for every deserialization.
+        assert!(Rc::ptr_eq(&first.name, &second.name));
+        assert!(!Rc::ptr_eq(&first.name, &fresh.name));
+    }
+
+    #[test]
+    fn test_owned_archive_cursor() {
+        use std::io::{Cursor, Write};
+
         let stub = ArchiveStub { hello: 4, world: 5 };
+        let bytes = rkyv::to_bytes::<rancor::Error>(&stub).unwrap();
+
+        let mut cursor = Cursor::new(Vec::new());
+        cursor.write_all(&bytes).unwrap();
+
+        let owned: OwnedArchive<ArchiveStub, _> =
+            OwnedArchive::new::<rancor::Error>(cursor).unwrap();
+        assert_eq!(stub, *owned);
+        assert_eq!(owned.container().position() as usize, bytes.len());
+        assert_eq!(owned.as_bytes(), bytes.as_slice());
+    }
+
+    #[test]
+    fn test_owned_archive_deserialize_boxed() {
+        trait Total {
+            fn total(&self) -> u64;
+        }
 
+        impl Total for ArchiveStub {
+            fn total(&self) -> u64 {
+                u64::from(self.hello) + self.world
+            }
+        }
+
+        let stub = ArchiveStub { hello: 4, world: 5 };
         let bytes = rkyv::to_bytes::<rancor::Error>(&stub).unwrap();
         let owned: OwnedArchive<ArchiveStub, _> =
             OwnedArchive::new::<rancor::Error>(bytes).unwrap();
 
-        // Finally check to see that both are equal.
-        assert_eq!(owned.hello, 4);
-        assert_eq!(owned.world, 5);
+        let boxed: Box<dyn Total> = owned
+            .deserialize_boxed::<dyn Total, rancor::Error>(|value| value)
+            .unwrap();
+        assert_eq!(boxed.total(), 9);
+    }
 
-        // Finally check to see that both are equal.
+    #[test]
+    fn test_diagnose() {
+        #[derive(Archive, Serialize)]
+        pub struct Flags {
+            id: u32,
+            enabled: bool,
+        }
+
+        let flags = Flags {
+            id: 1,
+            enabled: true,
+        };
+        let mut bytes = rkyv::to_bytes::<rancor::Error>(&flags).unwrap();
+        assert!(super::diagnose::<Flags>(&bytes).is_empty());
+
+        // Nothing past the length can be checked in a truncated buffer.
+        assert_eq!(
+            super::diagnose::<Flags>(&bytes[..4]),
+            [ValidationIssue::TooShort { len: 4, needed: 8 }]
+        );
+
+        munge!(let ArchivedFlags { enabled, .. } = unsafe {
+            rkyv::access_unchecked_mut::<ArchivedFlags>(&mut bytes)
+        });
+        // SAFETY: The corrupted archive is only passed to `diagnose`.
+        unsafe {
+            *(enabled.unseal_unchecked() as *mut bool).cast::<u8>() = 2;
+        }
+
+        let misaligned = Misaligned::new(&bytes);
+        let issues = super::diagnose::<Flags>(misaligned.bytes());
+        assert_eq!(issues.len(), 2);
+        assert_eq!(issues[0], ValidationIssue::Unaligned { align: 4 });
+        let ValidationIssue::Invalid { message } = &issues[1] else {
+            panic!("expected the corrupted field to be reported");
+        };
+        assert!(message.contains("enabled"), "{message}");
+
+        // Roots aligned beyond an `AlignedVec` are still checked in a copy.
+        #[derive(Archive, Serialize)]
+        #[rkyv(attr(repr(align(32))))]
+        pub struct Wide {
+            enabled: bool,
+        }
+
+        let bytes =
+            rkyv::to_bytes::<rancor::Error>(&Wide { enabled: true }).unwrap();
+        assert_eq!(align_of::<ArchivedWide>(), 32);
+        let mut buffer = AlignedVec::<64>::new();
+        buffer.extend_from_slice(&[0; 16]);
+        buffer.extend_from_slice(&bytes);
+        buffer[16] = 2;
+        let issues = super::diagnose::<Wide>(&buffer[16..]);
+        assert_eq!(issues.len(), 2);
+        assert_eq!(issues[0], ValidationIssue::Unaligned { align: 32 });
+        assert!(matches!(issues[1], ValidationIssue::Invalid { .. }));
+
+        // A root offset within its buffer is only reported as unaligned.
+        assert_eq!(
+            super::diagnose::<Wide>(&buffer),
+            [ValidationIssue::Unaligned { align: 32 }]
+        );
+    }
+
+    #[test]
+    fn test_owned_archive_from_vec() {
+        let stub = ArchiveStub { hello: 4, world: 5 };
+        let bytes = rkyv::to_bytes::<rancor::Error>(&stub).unwrap().to_vec();
+
+        // Whichever way the allocator aligned the `Vec`, the archive works.
+        let owned: OwnedArchive<ArchiveStub, _> =
+            OwnedArchive::from_vec::<rancor::Error>(bytes.clone()).unwrap();
+        assert_eq!(stub, *owned);
+
+        // Every `Vec` is aligned to 1, so it is kept.
+        let kept = BackedBytes::from_vec(bytes.clone(), 1);
+        assert!(matches!(kept, BackedBytes::Vec(_)));
+
+        // No `Vec` is aligned to twice its address' alignment, so it is
+        // copied.
+        let vec = bytes.clone();
+        let align = 2 << (vec.as_ptr() as usize).trailing_zeros();
+        let copied = BackedBytes::from_vec(vec, align);
+        assert!(matches!(copied, BackedBytes::Aligned(_)));
+        assert_eq!(&*copied, bytes.as_slice());
+
+        let owned: OwnedArchive<ArchiveStub, _> =
+            OwnedArchive::new::<rancor::Error>(copied).unwrap();
         assert_eq!(stub, *owned);
     }
 
     #[test]
-    fn test_owned_archive_vec_mut() {
+    fn test_owned_archive_container() {
         let stub = ArchiveStub { hello: 4, world: 5 };
+        let bytes = rkyv::to_bytes::<rancor::Error>(&stub).unwrap().to_vec();
+        let len = bytes.len();
+
+        let owned: OwnedArchive<ArchiveStub, Vec<u8>> =
+            OwnedArchive::new::<rancor::Error>(bytes).unwrap();
+        assert_eq!(owned.container().len(), len);
+        assert_eq!(owned.container().as_slice(), owned.as_bytes());
+        assert_eq!(stub, *owned);
+    }
 
+    #[test]
+    fn test_owned_archive_replace_with() {
+        let stub = ArchiveStub { hello: 4, world: 5 };
         let bytes = rkyv::to_bytes::<rancor::Error>(&stub).unwrap();
         let mut owned: OwnedArchive<ArchiveStub, _> =
+            OwnedArchive::new::<rancor::Error>(bytes.clone()).unwrap();
+
+        let next = ArchiveStub { hello: 6, world: 7 };
+        let next_bytes = rkyv::to_bytes::<rancor::Error>(&next).unwrap();
+        let old = owned.replace_with::<rancor::Error>(next_bytes).unwrap();
+        assert_eq!(old.as_slice(), bytes.as_slice());
+        assert_eq!(next, *owned);
+
+        // A buffer too short for the root fails validation.
+        let mut truncated = AlignedVec::<16>::new();
+        truncated.extend_from_slice(&bytes[..4]);
+        let (rejected, _) =
+            owned.replace_with::<rancor::Error>(truncated).unwrap_err();
+        assert_eq!(rejected.as_slice(), &bytes[..4]);
+        assert_eq!(next, *owned);
+    }
+
+    #[test]
+    fn test_owned_archive_to_owned_tuple() {
+        let record = (7u32, ArchiveStub { hello: 4, world: 5 });
+        let bytes = rkyv::to_bytes::<rancor::Error>(&record).unwrap();
+        let owned: OwnedArchive<(u32, ArchiveStub), _> =
             OwnedArchive::new::<rancor::Error>(bytes).unwrap();
 
-        // Check that they are the same.
+        let tuple = owned.to_owned_tuple::<rancor::Error>().unwrap();
+        assert_eq!(tuple, record);
+    }
+
+    #[test]
+    fn test_owned_archive_btree_map_iter() {
+        use std::collections::BTreeMap;
+
+        let map = [3, 1, 2]
+            .into_iter()
+            .map(|i| (format!("key {i}"), ArchiveStub { hello: i, world: 0 }))
+            .collect::<BTreeMap<_, _>>();
+        let bytes = rkyv::to_bytes::<rancor::Error>(&map).unwrap();
+        let owned: OwnedArchive<BTreeMap<String, ArchiveStub>, _> =
+            OwnedArchive::new::<rancor::Error>(bytes).unwrap();
+
+        let entries = owned
+            .iter()
+            .map(|(key, stub)| (key.as_str(), stub.hello))
+            .collect::<Vec<_>>();
+        assert_eq!(entries, [("key 1", 1), ("key 2", 2), ("key 3", 3)]);
+    }
+
+    #[test]
+    fn test_owned_archive_try_deref() {
+        #[derive(Archive, Serialize)]
+        pub struct Flag {
+            set: bool,
+        }
+
+        let bytes =
+            rkyv::to_bytes::<rancor::Error>(&Flag { set: true }).unwrap();
+        let mut owned: OwnedArchive<Flag, _> =
+            OwnedArchive::new::<rancor::Error>(bytes).unwrap();
+        assert!(owned.try_deref::<rancor::Error>().unwrap().set);
+
+        munge!(let ArchivedFlag { set } = owned.get_mut());
+        // SAFETY: The corrupted value is only ever read through `try_deref`,
+        // which revalidates first.
+        unsafe {
+            *(set.unseal_unchecked() as *mut bool).cast::<u8>() = 2;
+        }
+        assert!(owned.try_deref::<rancor::Error>().is_err());
+    }
+
+    #[test]
+    fn test_owned_archive_checked_clone() {
+        #[derive(Archive, Serialize)]
+        pub struct Flag {
+            set: bool,
+        }
+
+        let bytes =
+            rkyv::to_bytes::<rancor::Error>(&Flag { set: true }).unwrap();
+        let mut owned: OwnedArchive<Flag, _> =
+            OwnedArchive::new::<rancor::Error>(bytes).unwrap();
+        let snapshot = owned.checked_clone::<rancor::Error>().unwrap();
+
+        munge!(let ArchivedFlag { set } = owned.get_mut());
+        // SAFETY: The corrupted value is never read, `checked_clone`
+        // revalidates before handing out a clone.
+        unsafe {
+            *(set.unseal_unchecked() as *mut bool).cast::<u8>() = 2;
+        }
+        assert!(owned.checked_clone::<rancor::Error>().is_err());
+
+        // The snapshot taken before the corruption is unaffected.
+        assert!(snapshot.set);
+    }
+
+    #[test]
+    fn test_byte_backing_dispatch() {
+        fn dispatch<C: ByteBacking>(mut container: C) -> &'static str {
+            // Both ways of asking must agree.
+            assert_eq!(C::MUTABLE, container.try_bytes_mut().is_some());
+            if C::MUTABLE {
+                "mutable"
+            } else {
+                "read-only"
+            }
+        }
+
+        let bytes = rkyv::to_bytes::<rancor::Error>(&ArchiveStub {
+            hello: 4,
+            world: 5,
+        })
+        .unwrap();
+
+        assert_eq!(dispatch(bytes.to_vec()), "mutable");
+        assert_eq!(dispatch(Box::<[u8]>::from(bytes.as_slice())), "mutable");
+        assert_eq!(dispatch(Rc::<[u8]>::from(bytes.as_slice())), "read-only");
+        assert_eq!(dispatch(bytes.as_slice()), "read-only");
+        assert_eq!(dispatch(std::io::Cursor::new(bytes.to_vec())), "read-only");
+        assert_eq!(dispatch(Arc::new(bytes.to_vec())), "read-only");
+
+        let owned: OwnedArchive<ArchiveStub, _> =
+            OwnedArchive::new::<rancor::Error>(bytes).unwrap();
+        assert!(owned.is_mutable());
+        assert!(!owned.reborrow().is_mutable());
+    }
+
+    #[test]
+    fn test_owned_archive_from_reader_exact() {
+        let stub = ArchiveStub { hello: 4, world: 5 };
+        let bytes = rkyv::to_bytes::<rancor::Error>(&stub).unwrap();
+        let stream = [bytes.as_slice(), b"trailing data"].concat();
+
+        let mut reader = std::io::Cursor::new(stream.as_slice());
+        let owned = OwnedArchive::<ArchiveStub, _>::from_reader_exact::<
+            _,
+            rancor::Error,
+        >(&mut reader, bytes.len())
+        .unwrap();
         assert_eq!(stub, *owned);
+        assert_eq!(reader.position() as usize, bytes.len());
 
-        // Check mutability
-        munge!(let ArchivedArchiveStub { mut hello, ..} = owned.get_mut());
-        assert_eq!(*hello, 4);
+        // Asking for more than the stream holds is a short read.
+        let mut reader = &bytes[..8];
+        let result = OwnedArchive::<ArchiveStub, _>::from_reader_exact::<
+            _,
+            rancor::BoxedError,
+        >(&mut reader, bytes.len());
+        let Err(error) = result else {
+            panic!("a short read succeeded");
+        };
+        assert!(error.to_string().contains("stream ended"));
 
-        *hello = 9;
-        assert_eq!(owned.hello, 9);
+        // A huge length doesn't allocate for it before reading.
+        let mut reader = &bytes[..8];
+        let result = OwnedArchive::<ArchiveStub, _>::from_reader_exact::<
+            _,
+            rancor::BoxedError,
+        >(&mut reader, usize::MAX);
+        let Err(error) = result else {
+            panic!("a short read succeeded");
+        };
+        assert!(error.to_string().contains("stream ended"));
+    }
+
+    #[test]
+    fn test_owned_archive_bytes_arc() {
+        let stub = ArchiveStub { hello: 4, world: 5 };
+        let bytes = rkyv::to_bytes::<rancor::Error>(&stub).unwrap();
+
+        let owned: OwnedArchive<ArchiveStub, _> =
+            OwnedArchive::new::<rancor::Error>(bytes.to_vec()).unwrap();
+        let copied = owned.bytes_arc();
+        assert_eq!(&*copied, owned.as_bytes());
+        assert_ne!(copied.as_ptr(), owned.as_ptr());
+
+        let shared: Arc<[u8]> = Arc::from(bytes.as_slice());
+        let owned: OwnedArchive<ArchiveStub, _> =
+            OwnedArchive::new::<rancor::Error>(shared.clone()).unwrap();
+        assert_eq!(&*owned.bytes_arc(), owned.as_bytes());
+        assert!(Arc::ptr_eq(&owned.bytes_arc(), &shared));
+
+        let frozen = OwnedArchive::<ArchiveStub, _>::new::<rancor::Error>(
+            bytes.to_vec(),
+        )
+        .unwrap()
+        .freeze();
+        assert_eq!(&*frozen.bytes_arc(), frozen.as_bytes());
+    }
+
+    #[test]
+    fn test_owned_archive_new_aligned_to() {
+        #[derive(Archive, Serialize)]
+        #[rkyv(derive(Debug))]
+        pub struct Wide {
+            value: u128,
+        }
+
+        let bytes =
+            rkyv::to_bytes::<rancor::Error>(&Wide { value: 7 }).unwrap();
+        let align = align_of::<ArchivedWide>();
+        assert_eq!(align, 16);
+
+        // Shift the bytes so they are 8-byte aligned, but not 16-byte aligned.
+        let mut shifted = AlignedVec::<16>::new();
+        shifted.extend_from_slice(&[0; 8]);
+        shifted.extend_from_slice(&bytes);
+        let err =
+            OwnedArchive::<Wide, _>::new::<rancor::BoxedError>(&shifted[8..])
+                .unwrap_err()
+                .to_string();
+        assert!(err.contains("unaligned"));
+        assert!(err.contains("aligned to 16 bytes"));
+
+        let owned: OwnedArchive<Wide, AlignedVec<16>> =
+            OwnedArchive::new_aligned_to::<rancor::Error>(&shifted[8..])
+                .unwrap();
+        assert_eq!(owned.value, 7);
+
+        // Over-aligned buffers are byte backings like the default one.
+        let mut owned: OwnedArchive<Wide, AlignedVec<32>> =
+            OwnedArchive::new_aligned_to::<rancor::Error>(&bytes).unwrap();
+        assert!(owned.is_mutable());
+        munge!(let ArchivedWide { mut value } = owned.try_get_mut().unwrap());
+        *value = 8.into();
+        assert_eq!(owned.value, 8);
+        assert_eq!(&*owned.bytes_arc(), owned.as_bytes());
     }
 }
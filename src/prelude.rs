@@ -0,0 +1,48 @@
+//! Common imports for working with owned archives.
+//!
+//! ```
+//! use rkyv_util::prelude::*;
+//! ```
+
+#[cfg(feature = "mmap")]
+pub use crate::mmap::SharedMmap;
+pub use crate::{
+    cache::{CachedArchive, OwnedArchiveWithCache},
+    compat::ValidateArchive,
+    owned::{
+        BackedBytes, BorrowedArchive, BorrowedMutArchive, ByteBacking,
+        FromBytes, OwnedArchive, StableBytes, StableBytesMut, ValidationIssue,
+        ValidationLimits, ValidationReport,
+    },
+    strict::StrictOwnedArchive,
+    uninit::UninitArchive,
+};
+
+#[cfg(test)]
+mod tests {
+    use rkyv::{rancor, Archive, Serialize};
+
+    use crate::prelude::*;
+
+    #[derive(Archive, Serialize)]
+    pub struct Test {
+        hello: u8,
+    }
+
+    fn first_byte<C: StableBytes>(container: &C) -> u8 {
+        container.bytes()[0]
+    }
+
+    #[test]
+    fn test_prelude_imports() {
+        let mut bytes =
+            rkyv::to_bytes::<rancor::Error>(&Test { hello: 2 }).unwrap();
+        bytes.bytes_mut()[0] = 3;
+        assert_eq!(first_byte(&bytes), 3);
+
+        let mut owned_archive =
+            OwnedArchive::<Test, _>::new::<rancor::Error>(bytes).unwrap();
+        assert!(owned_archive.try_get_mut().is_some());
+        assert_eq!(owned_archive.hello, 3);
+    }
+}
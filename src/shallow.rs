@@ -0,0 +1,161 @@
+//! Owned archives that have only had their root checked.
+//!
+//! Full validation walks the entire archive, which can be too expensive to
+//! do up front for large untrusted inputs. [`ShallowArchive`] performs a
+//! constant-time sanity check of the root instead, so obviously broken
+//! inputs can be rejected cheaply before paying for the deep check in
+//! [`revalidate`](ShallowArchive::revalidate).
+
+use core::{fmt, marker::PhantomData};
+
+use rkyv::{rancor::Source, Archive, Portable};
+
+use crate::{
+    compat::ValidateArchive,
+    owned::{access_root, check_root, OwnedArchive, StableBytes},
+};
+
+/// An owned archive whose root has been checked, but nothing else.
+///
+/// # Safety guarantees
+/// [`new_shallow`](Self::new_shallow) only checks that the buffer is large
+/// enough to hold the root object and that the root is suitably aligned.
+/// It does not check any of the root's bytes, nor anything the root points
+/// to. Reading the archive is therefore `unsafe`: see [`get`](Self::get).
+///
+/// # Example
+/// ```
+/// use rkyv::rancor::Error;
+/// use rkyv_util::shallow::ShallowArchive;
+///
+/// #[derive(rkyv::Archive, rkyv::Serialize)]
+/// pub struct Test {
+///     hello: u8,
+/// }
+///
+/// let bytes = rkyv::to_bytes::<Error>(&Test { hello: 2 }).unwrap();
+///
+/// let shallow =
+///     ShallowArchive::<Test, _>::new_shallow::<Error>(bytes).unwrap();
+/// let owned_archive = shallow.revalidate::<Error>().unwrap();
+/// assert_eq!(owned_archive.hello, 2);
+/// ```
+pub struct ShallowArchive<T, C> {
+    /// The bytes of the archive, which have not been fully validated.
+    container: C,
+    _type: PhantomData<T>,
+}
+
+impl<T, C> ShallowArchive<T, C> {
+    /// Creates a new `ShallowArchive` from a container that supports the
+    /// `StableBytes` interface, checking only its root.
+    ///
+    /// This catches truncated and misaligned buffers, but not corruption
+    /// inside the archive.
+    pub fn new_shallow<E>(container: C) -> Result<Self, E>
+    where
+        T: Archive,
+        T::Archived: Portable,
+        E: Source,
+        C: StableBytes,
+    {
+        check_root::<T::Archived>(container.bytes()).map_err(E::new)?;
+        Ok(Self {
+            container,
+            _type: PhantomData,
+        })
+    }
+
+    /// Gets the archived root without validating it.
+    ///
+    /// # Safety
+    /// The bytes must be a valid archive of `T`, exactly as
+    /// [`OwnedArchive::new`] would check. Only the size and alignment of the
+    /// root have been checked so far.
+    pub unsafe fn get(&self) -> &T::Archived
+    where
+        T: Archive,
+        T::Archived: Portable,
+        C: StableBytes,
+    {
+        // # Safety
+        // The caller guarantees that the bytes are a valid archive of `T`.
+        unsafe { access_root::<T::Archived>(self.container.bytes()) }
+    }
+
+    /// Fully validates the archive, turning it into an `OwnedArchive`.
+    pub fn revalidate<E>(self) -> Result<OwnedArchive<T, C>, E>
+    where
+        T: Archive,
+        T::Archived: ValidateArchive<E>,
+        E: Source,
+        C: StableBytes,
+    {
+        OwnedArchive::new::<E>(self.container)
+    }
+}
+
+impl<T, C> fmt::Debug for ShallowArchive<T, C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ShallowArchive").finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rkyv::{rancor, util::AlignedVec, Archive, Serialize};
+
+    use super::ShallowArchive;
+
+    #[derive(Archive, Serialize)]
+    pub struct Test {
+        flags: Vec<bool>,
+    }
+
+    #[test]
+    fn test_shallow_catches_truncation() {
+        let bytes = rkyv::to_bytes::<rancor::Error>(&Test {
+            flags: vec![true, false],
+        })
+        .unwrap();
+
+        let mut truncated = AlignedVec::<16>::new();
+        truncated.extend_from_slice(&bytes[..2]);
+        let result =
+            ShallowArchive::<Test, _>::new_shallow::<rancor::Error>(truncated);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_shallow_get() {
+        // Two leading bytes leave the start misaligned, but not the root.
+        let bytes = rkyv::to_bytes::<rancor::Error>(&7u32).unwrap();
+        let mut buffer = AlignedVec::<16>::new();
+        buffer.extend_from_slice(&[0; 4]);
+        buffer.extend_from_slice(&bytes);
+
+        let shallow = ShallowArchive::<u32, _>::new_shallow::<rancor::Error>(
+            &buffer[2..],
+        )
+        .unwrap();
+        // SAFETY: The bytes were serialized from a `u32`.
+        assert_eq!(unsafe { *shallow.get() }, 7);
+        assert_eq!(*shallow.revalidate::<rancor::Error>().unwrap(), 7);
+    }
+
+    #[test]
+    fn test_shallow_misses_deep_corruption() {
+        let mut bytes = rkyv::to_bytes::<rancor::Error>(&Test {
+            flags: vec![true, false],
+        })
+        .unwrap();
+        // The vector's elements come first, so this corrupts the first
+        // `bool` without touching the root.
+        bytes[0] = 2;
+
+        let shallow =
+            ShallowArchive::<Test, _>::new_shallow::<rancor::Error>(bytes)
+                .unwrap();
+        assert!(shallow.revalidate::<rancor::Error>().is_err());
+    }
+}
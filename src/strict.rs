@@ -0,0 +1,176 @@
+//! An owned archive that revalidates its bytes on every access.
+//!
+//! [`OwnedArchive`] only validates once, when it is created, and then trusts
+//! that its bytes stay valid. [`StrictOwnedArchive`] trades speed for safety
+//! by running the full validation again every time it is dereferenced, so any
+//! corruption that happens after construction is caught as a panic instead of
+//! being read as undefined behavior.
+
+use core::{fmt::Debug, ops::Deref};
+
+use rkyv::{rancor, seal::Seal, Archive, Portable};
+
+use crate::{
+    compat::ValidateArchive,
+    owned::{OwnedArchive, StableBytes, StableBytesMut},
+};
+
+/// An owned archive that revalidates on every dereference.
+///
+/// # Performance
+/// Every call to `deref` walks and checks the entire archive, which costs as
+/// much as calling [`OwnedArchive::new`] again. This is linear in the size of
+/// the archive rather than the constant-time pointer cast that
+/// [`OwnedArchive`] uses, so it is best kept to debugging, tests and code
+/// that reads rarely but cannot tolerate silently reading corrupt data.
+///
+/// # Example
+/// ```
+/// use rkyv::rancor::Error;
+/// use rkyv_util::strict::StrictOwnedArchive;
+///
+/// #[derive(rkyv::Archive, rkyv::Serialize)]
+/// pub struct Test {
+///     hello: u8,
+/// }
+///
+/// let bytes = rkyv::to_bytes::<Error>(&Test { hello: 2 }).unwrap();
+///
+/// let strict = StrictOwnedArchive::<Test, _>::new::<Error>(bytes).unwrap();
+/// assert_eq!(strict.hello, 2);
+/// ```
+pub struct StrictOwnedArchive<T, C> {
+    /// The archive being revalidated.
+    inner: OwnedArchive<T, C>,
+}
+
+impl<T, C> StrictOwnedArchive<T, C> {
+    /// Creates a new `StrictOwnedArchive` from a container
+    /// that supports the `StableBytes` interface.
+    pub fn new<E>(container: C) -> Result<Self, E>
+    where
+        T: Archive,
+        T::Archived: ValidateArchive<E>,
+        E: rkyv::rancor::Source,
+        C: StableBytes,
+    {
+        Ok(Self {
+            inner: OwnedArchive::new::<E>(container)?,
+        })
+    }
+
+    /// Gets the pinned object as mutable.
+    ///
+    /// The next dereference will validate any changes made through the
+    /// returned seal.
+    pub fn get_mut(&mut self) -> Seal<'_, T::Archived>
+    where
+        T: Archive,
+        T::Archived: Portable,
+        C: StableBytesMut,
+    {
+        self.inner.get_mut()
+    }
+
+    /// Unwraps the archive, returning to validate-once semantics.
+    pub fn into_inner(self) -> OwnedArchive<T, C> {
+        self.inner
+    }
+}
+
+impl<T, C> From<OwnedArchive<T, C>> for StrictOwnedArchive<T, C> {
+    fn from(inner: OwnedArchive<T, C>) -> Self {
+        Self { inner }
+    }
+}
+
+impl<T, C> Deref for StrictOwnedArchive<T, C>
+where
+    T: Archive,
+    T::Archived: ValidateArchive<rancor::Error>,
+    C: StableBytes,
+{
+    type Target = T::Archived;
+
+    fn deref(&self) -> &Self::Target {
+        match self.inner.try_deref::<rancor::Error>() {
+            Ok(archived) => archived,
+            Err(e) => panic!("owned archive failed revalidation: {e}"),
+        }
+    }
+}
+
+impl<T, C: Clone> Clone for StrictOwnedArchive<T, C> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T, C> Debug for StrictOwnedArchive<T, C>
+where
+    T: Archive,
+    T::Archived: Debug + ValidateArchive<rancor::Error>,
+    C: StableBytes,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        self.deref().fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rkyv::{munge::munge, rancor, Archive, Serialize};
+
+    use super::StrictOwnedArchive;
+
+    #[derive(Archive, Serialize)]
+    pub struct FlagStub {
+        flag: bool,
+        value: u32,
+    }
+
+    #[test]
+    fn test_strict_archive_deref() {
+        let stub = FlagStub {
+            flag: true,
+            value: 7,
+        };
+
+        let bytes = rkyv::to_bytes::<rancor::Error>(&stub).unwrap();
+        let mut strict: StrictOwnedArchive<FlagStub, _> =
+            StrictOwnedArchive::new::<rancor::Error>(bytes).unwrap();
+        assert!(strict.flag);
+        assert_eq!(strict.value, 7);
+
+        // Valid edits pass revalidation.
+        munge!(let ArchivedFlagStub { mut flag, .. } = strict.get_mut());
+        *flag = false;
+        assert!(!strict.flag);
+    }
+
+    #[test]
+    #[should_panic(expected = "owned archive failed revalidation")]
+    fn test_strict_archive_corrupted_panics() {
+        let stub = FlagStub {
+            flag: true,
+            value: 7,
+        };
+
+        let bytes = rkyv::to_bytes::<rancor::Error>(&stub).unwrap();
+        let mut strict: StrictOwnedArchive<FlagStub, _> =
+            StrictOwnedArchive::new::<rancor::Error>(bytes).unwrap();
+
+        // Write a byte that is not a valid `bool`, simulating corruption that
+        // happened after the archive was created.
+        munge!(let ArchivedFlagStub { flag, .. } = strict.get_mut());
+        // SAFETY: The corrupted value is never read as a `bool`; the next
+        // dereference revalidates and panics first.
+        unsafe {
+            *(flag.unseal_unchecked() as *mut bool).cast::<u8>() = 2;
+        }
+
+        let _ = strict.flag;
+    }
+}
@@ -0,0 +1,265 @@
+//! Owned archives that can be shared across threads and replaced.
+
+use core::{fmt, ops::Deref};
+use std::sync::{Arc, PoisonError, RwLock, RwLockReadGuard};
+
+use rkyv::{util::AlignedVec, Archive};
+
+use crate::{
+    compat::ValidateArchive,
+    owned::{OwnedArchive, StableBytes},
+};
+
+/// An owned archive behind a `RwLock` that can be replaced wholesale.
+///
+/// # Locking
+/// Any number of threads can hold a read guard at once. Replacing the archive
+/// validates the new bytes *before* taking the write lock, so readers are
+/// only blocked for as long as it takes to swap the buffers. A writer waits
+/// for all current readers to drop their guards.
+///
+/// Because the archive is only ever swapped for one that has already been
+/// validated, it can never be observed in a partially written state. A panic
+/// in another thread while holding the lock therefore does not make the
+/// archive unusable, and lock poisoning is ignored.
+///
+/// # Example
+/// ```
+/// use rkyv::rancor::Error;
+/// use rkyv_util::{owned::OwnedArchive, sync::RwLockArchive};
+///
+/// #[derive(rkyv::Archive, rkyv::Serialize)]
+/// pub struct Test {
+///     hello: u8,
+/// }
+///
+/// let bytes = rkyv::to_bytes::<Error>(&Test { hello: 2 }).unwrap();
+/// let archive = RwLockArchive::new(
+///     OwnedArchive::<Test, _>::new::<Error>(bytes).unwrap(),
+/// );
+/// assert_eq!(archive.read().hello, 2);
+///
+/// let bytes = rkyv::to_bytes::<Error>(&Test { hello: 3 }).unwrap();
+/// archive.write_replace::<Error>(bytes).unwrap();
+/// assert_eq!(archive.read().hello, 3);
+/// ```
+pub struct RwLockArchive<T> {
+    /// The current archive.
+    lock: RwLock<OwnedArchive<T, AlignedVec>>,
+}
+
+impl<T> RwLockArchive<T> {
+    /// Creates a new `RwLockArchive` holding `archive`.
+    pub fn new(archive: OwnedArchive<T, AlignedVec>) -> Self {
+        Self {
+            lock: RwLock::new(archive),
+        }
+    }
+
+    /// Locks the archive for reading.
+    ///
+    /// The current thread is blocked while a replacement is being swapped
+    /// in.
+    pub fn read(&self) -> RwLockReadGuard<'_, OwnedArchive<T, AlignedVec>> {
+        self.lock.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Validates `bytes` and replaces the archive with them.
+    ///
+    /// Returns the bytes of the archive that was replaced. If validation
+    /// fails, the current archive is left untouched.
+    pub fn write_replace<E>(&self, bytes: AlignedVec) -> Result<AlignedVec, E>
+    where
+        T: Archive,
+        T::Archived: ValidateArchive<E>,
+        E: rkyv::rancor::Source,
+    {
+        let archive = OwnedArchive::new::<E>(bytes)?;
+
+        let mut guard =
+            self.lock.write().unwrap_or_else(PoisonError::into_inner);
+        let previous = core::mem::replace(&mut *guard, archive);
+        drop(guard);
+
+        Ok(previous.into_container())
+    }
+
+    /// Consumes the lock, returning the current archive.
+    pub fn into_inner(self) -> OwnedArchive<T, AlignedVec> {
+        self.lock
+            .into_inner()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// A cheaply cloneable handle to an owned archive.
+///
+/// This is an `Arc<OwnedArchive<T, C>>` that dereferences straight to the
+/// archived root, so fields can be read as `shared.field` instead of
+/// `(**shared).field`. Cloning only clones the `Arc`.
+///
+/// # Example
+/// ```
+/// use std::thread;
+///
+/// use rkyv::rancor::Error;
+/// use rkyv_util::{owned::OwnedArchive, sync::SharedArchive};
+///
+/// #[derive(rkyv::Archive, rkyv::Serialize)]
+/// pub struct Test {
+///     hello: u8,
+/// }
+///
+/// let bytes = rkyv::to_bytes::<Error>(&Test { hello: 2 }).unwrap();
+/// let shared = SharedArchive::new(
+///     OwnedArchive::<Test, _>::new::<Error>(bytes).unwrap(),
+/// );
+///
+/// let handle = shared.clone();
+/// thread::spawn(move || assert_eq!(handle.hello, 2))
+///     .join()
+///     .unwrap();
+/// assert_eq!(shared.hello, 2);
+/// ```
+pub struct SharedArchive<T, C> {
+    /// The shared archive.
+    archive: Arc<OwnedArchive<T, C>>,
+}
+
+impl<T, C> SharedArchive<T, C> {
+    /// Creates a new `SharedArchive` holding `archive`.
+    pub fn new(archive: OwnedArchive<T, C>) -> Self {
+        Self {
+            archive: Arc::new(archive),
+        }
+    }
+
+    /// Gets the underlying owned archive.
+    pub fn archive(&self) -> &OwnedArchive<T, C> {
+        &self.archive
+    }
+
+    /// Returns whether both handles share the same archive.
+    pub fn ptr_eq(this: &Self, other: &Self) -> bool {
+        Arc::ptr_eq(&this.archive, &other.archive)
+    }
+}
+
+impl<T, C> Clone for SharedArchive<T, C> {
+    fn clone(&self) -> Self {
+        Self {
+            archive: self.archive.clone(),
+        }
+    }
+}
+
+impl<T, C> From<OwnedArchive<T, C>> for SharedArchive<T, C> {
+    fn from(archive: OwnedArchive<T, C>) -> Self {
+        Self::new(archive)
+    }
+}
+
+impl<T, C> From<Arc<OwnedArchive<T, C>>> for SharedArchive<T, C> {
+    fn from(archive: Arc<OwnedArchive<T, C>>) -> Self {
+        Self { archive }
+    }
+}
+
+impl<T: Archive, C: StableBytes> Deref for SharedArchive<T, C> {
+    type Target = T::Archived;
+
+    fn deref(&self) -> &Self::Target {
+        self.archive.deref()
+    }
+}
+
+impl<T: Archive, C: StableBytes> fmt::Debug for SharedArchive<T, C>
+where
+    T::Archived: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.deref().fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::Barrier, thread};
+
+    use rkyv::{rancor, util::AlignedVec, Archive, Serialize};
+
+    use super::{RwLockArchive, SharedArchive};
+    use crate::owned::OwnedArchive;
+
+    #[derive(Archive, Serialize)]
+    pub struct Version {
+        number: u32,
+        /// Always `number * 2`, to catch readers seeing mixed versions.
+        double: u32,
+    }
+
+    fn version(number: u32) -> AlignedVec {
+        rkyv::to_bytes::<rancor::Error>(&Version {
+            number,
+            double: number * 2,
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_rwlock_archive_concurrent_reads_and_replace() {
+        let archive = RwLockArchive::new(
+            OwnedArchive::<Version, _>::new::<rancor::Error>(version(0))
+                .unwrap(),
+        );
+        let barrier = Barrier::new(5);
+
+        thread::scope(|s| {
+            for _ in 0..4 {
+                s.spawn(|| {
+                    barrier.wait();
+                    let mut last = 0;
+                    for _ in 0..1000 {
+                        let guard = archive.read();
+                        assert_eq!(guard.double, guard.number * 2);
+                        // Versions only move forward.
+                        assert!(guard.number >= last);
+                        last = guard.number.to_native();
+                    }
+                });
+            }
+
+            barrier.wait();
+            for number in 1..=10 {
+                archive
+                    .write_replace::<rancor::Error>(version(number))
+                    .unwrap();
+            }
+        });
+
+        assert_eq!(archive.read().number, 10);
+
+        // A replacement that fails validation keeps the current archive.
+        let mut truncated = version(11);
+        truncated.resize(1, 0);
+        assert!(archive.write_replace::<rancor::Error>(truncated).is_err());
+        assert_eq!(archive.into_inner().number, 10);
+    }
+
+    #[test]
+    fn test_shared_archive_clones_share_archive() {
+        let shared = SharedArchive::new(
+            OwnedArchive::<Version, _>::new::<rancor::Error>(version(3))
+                .unwrap(),
+        );
+        assert_eq!(shared.number, 3);
+
+        let clone = shared.clone();
+        assert!(SharedArchive::ptr_eq(&shared, &clone));
+        assert!(core::ptr::eq(&*shared, &*clone));
+
+        thread::scope(|s| {
+            s.spawn(|| assert_eq!(clone.double, 6));
+        });
+    }
+}
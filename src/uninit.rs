@@ -0,0 +1,133 @@
+//! Buffers that are filled in before they are validated as archives.
+//!
+//! Some archives are written into a buffer that was allocated up front, for
+//! example one sized for the largest possible record. [`UninitArchive`] holds
+//! such a buffer while it is being filled, and only becomes an
+//! [`OwnedArchive`] once [`finalize`](UninitArchive::finalize) has validated
+//! what was written.
+
+use core::{fmt, marker::PhantomData};
+
+use rkyv::{rancor::Source, Archive};
+
+use crate::{
+    compat::ValidateArchive,
+    owned::{OwnedArchive, StableBytes, StableBytesMut},
+};
+
+/// A buffer that is being filled in with an archive of `T`.
+///
+/// # Example
+/// ```
+/// use rkyv::rancor::Error;
+/// use rkyv_util::owned::OwnedArchive;
+///
+/// #[derive(rkyv::Archive, rkyv::Serialize)]
+/// pub struct Test {
+///     hello: u8,
+/// }
+///
+/// let bytes = rkyv::to_bytes::<Error>(&Test { hello: 2 }).unwrap();
+///
+/// let buffer = vec![0; bytes.len()];
+/// let mut uninit = OwnedArchive::<Test, _>::reserve_uninit(buffer);
+/// uninit.bytes_mut().copy_from_slice(&bytes);
+/// let owned_archive = uninit.finalize::<Error>().unwrap();
+/// assert_eq!(owned_archive.hello, 2);
+/// ```
+pub struct UninitArchive<T, C> {
+    /// The buffer being filled in.
+    container: C,
+    _type: PhantomData<T>,
+}
+
+impl<T, C> OwnedArchive<T, C> {
+    /// Reserves `container` for an archive of `T` that has yet to be written.
+    ///
+    /// Nothing is checked until [`UninitArchive::finalize`].
+    pub fn reserve_uninit(container: C) -> UninitArchive<T, C> {
+        UninitArchive {
+            container,
+            _type: PhantomData,
+        }
+    }
+}
+
+impl<T, C> UninitArchive<T, C> {
+    /// Gets the buffer to write the archive into.
+    pub fn bytes_mut(&mut self) -> &mut [u8]
+    where
+        C: StableBytesMut,
+    {
+        self.container.bytes_mut()
+    }
+
+    /// Validates the buffer, turning it into an `OwnedArchive`.
+    ///
+    /// If validation fails, the buffer is handed back with the error so that
+    /// writing can continue.
+    pub fn finalize<E>(self) -> Result<OwnedArchive<T, C>, (Self, E)>
+    where
+        T: Archive,
+        T::Archived: ValidateArchive<E>,
+        E: Source,
+        C: StableBytes,
+    {
+        OwnedArchive::try_new::<E>(self.container).map_err(|(container, e)| {
+            let uninit = Self {
+                container,
+                _type: PhantomData,
+            };
+            (uninit, e)
+        })
+    }
+
+    /// Unwraps the underlying container.
+    pub fn into_inner(self) -> C {
+        self.container
+    }
+}
+
+impl<T, C> fmt::Debug for UninitArchive<T, C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UninitArchive").finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::num::NonZeroU32;
+
+    use rkyv::{rancor, util::AlignedVec, Archive, Serialize};
+
+    use crate::owned::OwnedArchive;
+
+    #[derive(Archive, Serialize)]
+    pub struct Record {
+        id: NonZeroU32,
+        score: u32,
+    }
+
+    #[test]
+    fn test_finalize_after_writing() {
+        let record = Record {
+            id: NonZeroU32::new(7).unwrap(),
+            score: 70,
+        };
+        let bytes = rkyv::to_bytes::<rancor::Error>(&record).unwrap();
+
+        let mut buffer = AlignedVec::<16>::new();
+        buffer.resize(bytes.len(), 0);
+        let uninit = OwnedArchive::<Record, _>::reserve_uninit(buffer);
+
+        // An all-zero id is not a valid `NonZeroU32`.
+        let Err((mut uninit, _)) = uninit.finalize::<rancor::Error>() else {
+            panic!("an unwritten buffer passed validation");
+        };
+
+        uninit.bytes_mut().copy_from_slice(&bytes);
+        let archive = uninit.finalize::<rancor::Error>().unwrap();
+        assert_eq!(archive.id.get(), 7);
+        assert_eq!(archive.score, 70);
+    }
+}
@@ -0,0 +1,117 @@
+//! Weak references to the bytes of owned archives.
+//!
+//! This is useful for caches that allow their entries to be evicted: a
+//! [`WeakArchive`] does not keep the bytes alive, but can be upgraded back to
+//! an [`OwnedArchive`] for as long as someone else does.
+
+use std::{
+    marker::PhantomData,
+    sync::{Arc, Weak},
+};
+
+use crate::owned::OwnedArchive;
+
+/// A weak reference to the bytes of an [`OwnedArchive`].
+///
+/// The bytes were validated when the original [`OwnedArchive`] was created,
+/// so [`upgrade`](Self::upgrade) does not need to validate them again.
+///
+/// # Example
+/// ```
+/// use std::sync::Arc;
+///
+/// use rkyv::rancor::Error;
+/// use rkyv_util::{owned::OwnedArchive, weak::WeakArchive};
+///
+/// #[derive(rkyv::Archive, rkyv::Serialize)]
+/// pub struct Test {
+///     hello: u8,
+/// }
+///
+/// let bytes = rkyv::to_bytes::<Error>(&Test { hello: 2 }).unwrap();
+/// let bytes: Arc<[u8]> = Arc::from(bytes.as_slice());
+///
+/// let owned_archive = OwnedArchive::<Test, _>::new::<Error>(bytes).unwrap();
+/// let weak: WeakArchive<Test> = owned_archive.downgrade();
+/// assert_eq!(weak.upgrade().unwrap().hello, 2);
+///
+/// drop(owned_archive);
+/// assert!(weak.upgrade().is_none());
+/// ```
+pub struct WeakArchive<T> {
+    /// The weak reference to the validated bytes.
+    bytes: Weak<[u8]>,
+    _type: PhantomData<T>,
+}
+
+impl<T> OwnedArchive<T, Arc<[u8]>> {
+    /// Creates a weak reference to the bytes of this archive.
+    pub fn downgrade(&self) -> WeakArchive<T> {
+        WeakArchive {
+            bytes: Arc::downgrade(self.container()),
+            _type: PhantomData,
+        }
+    }
+}
+
+impl<T> WeakArchive<T> {
+    /// Recovers the `OwnedArchive`, if its bytes are still alive.
+    pub fn upgrade(&self) -> Option<OwnedArchive<T, Arc<[u8]>>> {
+        let bytes = self.bytes.upgrade()?;
+        // # Safety
+        // The bytes were taken from an `OwnedArchive<T, Arc<[u8]>>` and
+        // cannot have moved or changed since, as they are shared.
+        Some(unsafe { OwnedArchive::new_unchecked(bytes) })
+    }
+}
+
+impl<T> Clone for WeakArchive<T> {
+    fn clone(&self) -> Self {
+        Self {
+            bytes: self.bytes.clone(),
+            _type: PhantomData,
+        }
+    }
+}
+
+impl<T> core::fmt::Debug for WeakArchive<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("WeakArchive")
+            .field("strong_count", &self.bytes.strong_count())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use rkyv::{rancor, Archive, Serialize};
+
+    use crate::owned::OwnedArchive;
+
+    #[derive(Archive, Serialize)]
+    pub struct Test {
+        hello: u8,
+    }
+
+    #[test]
+    fn test_weak_archive_upgrade() {
+        let bytes =
+            rkyv::to_bytes::<rancor::Error>(&Test { hello: 3 }).unwrap();
+        let bytes: Arc<[u8]> = Arc::from(bytes.as_slice());
+
+        let first =
+            OwnedArchive::<Test, _>::new::<rancor::Error>(bytes).unwrap();
+        let second = first.clone();
+        let weak = first.downgrade();
+
+        drop(first);
+        assert_eq!(weak.upgrade().unwrap().hello, 3);
+
+        // Once every strong reference is gone, the bytes are too.
+        drop(second);
+        assert!(weak.upgrade().is_none());
+        assert!(weak.clone().upgrade().is_none());
+    }
+}
@@ -0,0 +1,122 @@
+//! Loading owned archives from entries of zip files.
+//!
+//! Bundles of configuration or assets are often shipped as zip files. This
+//! reads an entry straight into an aligned buffer and validates it, without
+//! extracting it to disk first.
+
+use std::io::{Read, Seek};
+
+use ::zip::ZipArchive;
+use rkyv::{rancor::Source, util::AlignedVec, Archive};
+
+use crate::{compat::ValidateArchive, owned::OwnedArchive};
+
+/// The most bytes reserved up front for an entry before reading it.
+const MAX_RESERVE: usize = 1 << 20;
+
+/// Reads the entry named `entry_name` from a zip file and validates it as an
+/// archive of `T`.
+///
+/// Errors from the zip file, such as a missing entry, are reported through
+/// `E` alongside validation errors.
+///
+/// # Example
+/// ```
+/// use std::io::{Cursor, Write};
+///
+/// use rkyv::rancor::Error;
+/// use rkyv_util::zip::read_zip_entry_owned;
+/// use zip::{write::SimpleFileOptions, ZipArchive, ZipWriter};
+///
+/// #[derive(rkyv::Archive, rkyv::Serialize)]
+/// pub struct Test {
+///     hello: u8,
+/// }
+///
+/// let bytes = rkyv::to_bytes::<Error>(&Test { hello: 2 }).unwrap();
+///
+/// let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+/// writer
+///     .start_file("test.rkyv", SimpleFileOptions::default())
+///     .unwrap();
+/// writer.write_all(&bytes).unwrap();
+/// let zip = writer.finish().unwrap();
+///
+/// let mut zip = ZipArchive::new(zip).unwrap();
+/// let owned_archive =
+///     read_zip_entry_owned::<Test, Error, _>(&mut zip, "test.rkyv").unwrap();
+/// assert_eq!(owned_archive.hello, 2);
+/// ```
+pub fn read_zip_entry_owned<T, E, R>(
+    archive: &mut ZipArchive<R>,
+    entry_name: &str,
+) -> Result<OwnedArchive<T, AlignedVec>, E>
+where
+    T: Archive,
+    T::Archived: ValidateArchive<E>,
+    E: Source,
+    R: Read + Seek,
+{
+    let mut entry = archive.by_name(entry_name).map_err(E::new)?;
+    // The size comes from the entry's header, which the zip's author
+    // controls, so it only sizes a bounded initial reservation.
+    let reserve =
+        usize::try_from(entry.size()).map_or(0, |size| size.min(MAX_RESERVE));
+    let mut bytes = AlignedVec::with_capacity(reserve);
+    bytes.extend_from_reader(&mut entry).map_err(E::new)?;
+    OwnedArchive::new::<E>(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Cursor, Write};
+
+    use ::zip::{
+        write::SimpleFileOptions, CompressionMethod, ZipArchive, ZipWriter,
+    };
+    use rkyv::{rancor, Archive, Serialize};
+
+    use super::read_zip_entry_owned;
+
+    #[derive(Archive, Serialize)]
+    pub struct Test {
+        values: Vec<u32>,
+    }
+
+    #[test]
+    fn test_read_zip_entry_owned() {
+        let bytes = rkyv::to_bytes::<rancor::Error>(&Test {
+            values: (0..100).collect(),
+        })
+        .unwrap();
+
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        writer
+            .start_file("readme.txt", SimpleFileOptions::default())
+            .unwrap();
+        writer.write_all(b"not an archive").unwrap();
+        writer
+            .start_file(
+                "test.rkyv",
+                SimpleFileOptions::default()
+                    .compression_method(CompressionMethod::Deflated),
+            )
+            .unwrap();
+        writer.write_all(&bytes).unwrap();
+        let mut zip = ZipArchive::new(writer.finish().unwrap()).unwrap();
+
+        let owned = read_zip_entry_owned::<Test, rancor::Error, _>(
+            &mut zip,
+            "test.rkyv",
+        )
+        .unwrap();
+        assert_eq!(owned.values.len(), 100);
+        assert_eq!(owned.values[99], 99);
+
+        let missing = read_zip_entry_owned::<Test, rancor::Error, _>(
+            &mut zip,
+            "missing.rkyv",
+        );
+        assert!(missing.is_err());
+    }
+}